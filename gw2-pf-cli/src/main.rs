@@ -0,0 +1,255 @@
+use std::{ffi::OsString, io::Write, path::{Path, PathBuf}, process::ExitCode, sync::Arc};
+use gw2_pf_rs::{export, formats, formats::{abnk::bkck::v2::ASNDFile, asnd::asnd::v2::{PayloadKind, WaveformData}}, observe::TraceObserver, parse::{ChunkIter, ParseMagicVariant}, pf::{Magic, PackFileReader, RawPackFile}};
+
+const USAGE : &str = "\
+Usage: gw2-pf <command> [arguments]
+
+Commands:
+  info <file>...                      Print the packfile header and the list of chunks.
+  dump [--json] [--trace] <file> [<output>]
+                                      Print the parsed chunks using Debug formatting or as JSON.
+                                      Writes to stdout if no output is given.
+                                      --trace writes every field and pointer the parser reads to stderr.
+  coverage <file>...                  List the bytes of each chunk that the parser never read.
+  extract-audio [--metadata] [--as-wav] <file> <output dir>
+                                      Write the audio payloads of an ABNK or ASND file.
+                                      --metadata writes the length and loop points into a .json file next to
+                                      each payload and into an ID3 tag of mp3 files.
+                                      --as-wav writes unrecognized payloads as 16 bit PCM .wav files. They are
+                                      not checked, so only use this for files known to contain raw samples.
+  export-voices <file> <output.csv>   Write the text to voice mappings of a txtv file.
+";
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+fn main() -> ExitCode {
+	let args = std::env::args_os().skip(1).collect::<Vec<_>>();
+	match run(args) {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(e) => {
+			eprintln!("error: {e}");
+			ExitCode::FAILURE
+		},
+	}
+}
+
+fn run(args : Vec<OsString>) -> Result<()> {
+	let mut args = args.into_iter();
+	let Some(command) = args.next() else {
+		print!("{USAGE}");
+		return Ok(())
+	};
+
+	match command.to_str() {
+		Some("info") => {
+			let paths = args.collect::<Vec<_>>();
+			if paths.is_empty() { return Err(usage_error("missing input file")) }
+			for path in paths {
+				info(Path::new(&path))?;
+			}
+			Ok(())
+		},
+		Some("dump") => {
+			let mut json = false;
+			let mut trace = false;
+			let mut paths = Vec::new();
+			for arg in args {
+				if arg == "--json" { json = true }
+				else if arg == "--trace" { trace = true }
+				else { paths.push(PathBuf::from(arg)) }
+			}
+			match paths.as_slice() {
+				[input] => dump(input, json, trace, &mut std::io::stdout().lock()),
+				[input, output] => dump(input, json, trace, &mut std::io::BufWriter::new(std::fs::File::create(output)?)),
+				_ => Err(usage_error("expected an input and an optional output path")),
+			}
+		},
+		Some("coverage") => {
+			let paths = args.collect::<Vec<_>>();
+			if paths.is_empty() { return Err(usage_error("missing input file")) }
+			for path in paths {
+				coverage(Path::new(&path))?;
+			}
+			Ok(())
+		},
+		Some("extract-audio") => {
+			let mut metadata = false;
+			let mut as_wav = false;
+			let [input, output] = two_paths(args.filter(|arg| {
+				if arg == "--metadata" { metadata = true; false }
+				else if arg == "--as-wav" { as_wav = true; false }
+				else { true }
+			}))?;
+			extract_audio(&input, &output, metadata, as_wav)
+		},
+		Some("export-voices") => {
+			let [input, output] = two_paths(args)?;
+			export_voices(&input, &output)
+		},
+		Some("help" | "--help" | "-h") => {
+			print!("{USAGE}");
+			Ok(())
+		},
+		_ => Err(usage_error(&format!("unknown command {command:?}"))),
+	}
+}
+
+fn usage_error(message : &str) -> Box<dyn std::error::Error> {
+	format!("{message}\n\n{USAGE}").into()
+}
+
+fn two_paths(args : impl Iterator<Item = OsString>) -> Result<[PathBuf; 2]> {
+	let paths = args.map(PathBuf::from).collect::<Vec<_>>();
+	paths.try_into().map_err(|_| usage_error("expected an input and an output path"))
+}
+
+fn read(path : &Path) -> Result<Vec<u8>> {
+	std::fs::read(path).map_err(|e| format!("failed to read {}: {e}", path.display()).into())
+}
+
+/// Formats a FourCC, trailing zeros are dropped and non printable bytes escaped.
+fn magic_to_string(magic : u32) -> String {
+	let bytes = magic.to_le_bytes();
+	let length = bytes.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+	bytes[..length].escape_ascii().to_string()
+}
+
+fn info(path : &Path) -> Result<()> {
+	let data = read(path)?;
+	let file = RawPackFile::from_bytes(&data)?;
+
+	println!("{}", path.display());
+	println!("  type: {}, flags: {:#x} ({}-bit pointers), header size: {}",
+		magic_to_string(file.header.file_type), file.header.flags, if file.header.is_64_bit() { 64 } else { 32 }, file.header.header_size);
+	for chunk in file.chunks {
+		let chunk = chunk?;
+		println!("  chunk {} v{}: header size: {}, descriptor offset: {}, data: {} bytes",
+			magic_to_string(chunk.magic()), chunk.version(), chunk.header.chunk_header_size, chunk.header.descriptor_offset, chunk.data.len());
+	}
+	Ok(())
+}
+
+fn coverage(path : &Path) -> Result<()> {
+	fn print_coverage<'inp, F : ParseMagicVariant<'inp>>(file : RawPackFile<'inp>) -> Result<()> {
+		for chunk in file.chunks {
+			let chunk = chunk?;
+			let (_, unread) = chunk.parse_with_coverage::<F>()?;
+			let range = chunk.data_range();
+			println!("  chunk {} v{} {:#x}..{:#x}: {} unread bytes",
+				magic_to_string(chunk.magic()), chunk.version(), range.start, range.end, unread.iter().map(|r| r.len()).sum::<usize>());
+			for range in unread {
+				println!("    {:#x}..{:#x} ({} bytes)", range.start, range.end, range.len());
+			}
+		}
+		Ok(())
+	}
+
+	let data = read(path)?;
+	let file = RawPackFile::from_bytes(&data)?;
+	println!("{}", path.display());
+	match file.header.file_type {
+		formats::ABIX::MAGIC => print_coverage::<formats::ABIX>(file),
+		formats::ABNK::MAGIC => print_coverage::<formats::ABNK>(file),
+		formats::ASND::MAGIC => print_coverage::<formats::ASND>(file),
+		formats::txtv::MAGIC => print_coverage::<formats::txtv>(file),
+		other => Err(format!("unsupported file type {}", magic_to_string(other)).into()),
+	}
+}
+
+fn dump(path : &Path, json : bool, trace : bool, output : &mut dyn Write) -> Result<()> {
+	fn dump_chunks<'inp, F>(chunks : ChunkIter<'inp, F>, json : bool, trace : bool, output : &mut dyn Write) -> Result<()>
+		where F : Magic + ParseMagicVariant<'inp> + std::fmt::Debug + serde::Serialize
+	{
+		let chunks = if trace { chunks.with_observer(Arc::new(TraceObserver::stderr())) } else { chunks };
+		let chunks = chunks.collect::<gw2_pf_rs::parse::Result<Vec<_>>>()?;
+		if json {
+			serde_json::to_writer_pretty(&mut *output, &chunks)?;
+			writeln!(output)?;
+		}
+		else {
+			for chunk in chunks {
+				writeln!(output, "{chunk:#?}")?;
+			}
+		}
+		output.flush()?;
+		Ok(())
+	}
+
+	let data = read(path)?;
+	let file_type = RawPackFile::from_bytes(&data)?.header.file_type;
+	match file_type {
+		formats::ABIX::MAGIC => dump_chunks(PackFileReader::<formats::ABIX>::from_bytes(&data)?, json, trace, output),
+		formats::ABNK::MAGIC => dump_chunks(PackFileReader::<formats::ABNK>::from_bytes(&data)?, json, trace, output),
+		formats::ASND::MAGIC => dump_chunks(PackFileReader::<formats::ASND>::from_bytes(&data)?, json, trace, output),
+		formats::txtv::MAGIC => dump_chunks(PackFileReader::<formats::txtv>::from_bytes(&data)?, json, trace, output),
+		other => Err(format!("unsupported file type {}, use `info` to list the raw chunks", magic_to_string(other)).into()),
+	}
+}
+
+fn extract_audio(path : &Path, output_dir : &Path, metadata : bool, as_wav : bool) -> Result<()> {
+	let write_waveform = |waveform : &WaveformData, name : &str, index : usize, entry : Option<&ASNDFile>| -> Result<()> {
+		let mut info = export::AudioMetadata::from_waveform(waveform);
+		if let Some(entry) = entry { info = info.with_bank_entry(entry) }
+
+		let (extension, payload) = match waveform.detect_payload_kind() {
+			PayloadKind::Mp3 if metadata && !waveform.audio_data.starts_with(b"ID3") => ("mp3", export::mp3_with_metadata(waveform.audio_data, &info)?),
+			PayloadKind::Unknown if as_wav => ("wav", export::wav(waveform)?),
+			kind => (kind.extension(), waveform.audio_data.to_vec()),
+		};
+		let path = output_dir.join(format!("{name}_{index}.{extension}"));
+		std::fs::write(&path, payload)?;
+		println!("{}", path.display());
+
+		if metadata {
+			std::fs::write(path.with_extension("json"), serde_json::to_string_pretty(&info)?)?;
+		}
+		Ok(())
+	};
+
+	let data = read(path)?;
+	let file_type = RawPackFile::from_bytes(&data)?.header.file_type;
+	std::fs::create_dir_all(output_dir)?;
+	match file_type {
+		formats::ABNK::MAGIC => {
+			// broken entries are reported and skipped, so the rest of the bank is still extracted
+			let mut failed = 0;
+			for chunk in PackFileReader::<formats::ABNK>::from_bytes(&data)? {
+				let bank = match chunk {
+					Ok(bank) => bank,
+					Err(e) => { eprintln!("error: {e}"); failed += 1; continue },
+				};
+				for waveform in bank.waveforms() {
+					match waveform {
+						Ok(w) => write_waveform(&w.waveform, &w.voice_id.to_string(), w.index, Some(w.entry))?,
+						Err(e) => { eprintln!("error: {e}"); failed += 1 },
+					}
+				}
+			}
+			if failed > 0 { return Err(format!("{failed} entries could not be read").into()) }
+			Ok(())
+		},
+		formats::ASND::MAGIC => {
+			let name = path.file_stem().map_or("sound".into(), |s| s.to_string_lossy());
+			for (i, chunk) in PackFileReader::<formats::ASND>::from_bytes(&data)?.enumerate() {
+				write_waveform(&*chunk?, &name, i, None)?;
+			}
+			Ok(())
+		},
+		other => Err(format!("expected an ABNK or ASND file, got {}", magic_to_string(other)).into()),
+	}
+}
+
+fn export_voices(path : &Path, output : &Path) -> Result<()> {
+	let data = read(path)?;
+	let destination = &mut std::io::BufWriter::new(std::fs::File::create(output)?);
+
+	writeln!(destination, "textId;voiceId")?;
+	for chunk in PackFileReader::<formats::txtv>::from_bytes(&data)? {
+		let chunk = chunk?;
+		for mapping in &chunk.mappings {
+			writeln!(destination, "{};{}", mapping.text_id, mapping.voice_id)?;
+		}
+	}
+	destination.flush()?;
+	Ok(())
+}
@@ -0,0 +1,185 @@
+use std::{path::PathBuf, process::{Command, Output}};
+use gw2_pf_rs::formats::{self, asnd::{asnd, asnd::v2::WaveformData}};
+
+fn run(args : &[&str]) -> Output {
+	Command::new(env!("CARGO_BIN_EXE_gw2-pf")).args(args).output().unwrap()
+}
+
+fn temp_dir(name : &str) -> PathBuf {
+	let dir = std::env::temp_dir().join(format!("gw2-pf-cli-{name}-{}", std::process::id()));
+	std::fs::create_dir_all(&dir).unwrap();
+	dir
+}
+
+#[test]
+fn info() {
+	let output = run(&["info", "../gw2-pf/tests/res/179764.abnk"]);
+	assert!(output.status.success());
+	let stdout = String::from_utf8(output.stdout).unwrap();
+	assert!(stdout.contains("type: ABNK"), "{stdout}");
+	assert!(stdout.contains("chunk BKCK"), "{stdout}");
+}
+
+#[test]
+fn dump_json() {
+	let dir = temp_dir("dump");
+	let path = dir.join("198300.json");
+	let output = run(&["dump", "--json", "../gw2-pf/tests/res/198300.txtv", path.to_str().unwrap()]);
+	assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+	let json : serde_json::Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+	_ = std::fs::remove_dir_all(&dir);
+	assert!(json[0]["txtv"]["V0"]["mappings"][0]["voice_id"].is_u64(), "{json}");
+}
+
+#[test]
+fn dump_debug() {
+	let output = run(&["dump", "../gw2-pf/tests/res/190445.abix"]);
+	assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+	assert!(String::from_utf8(output.stdout).unwrap().contains("BankIndexData"));
+}
+
+#[test]
+fn dump_trace() {
+	let output = run(&["dump", "--trace", "../gw2-pf/tests/res/179764.abnk", "/dev/null"]);
+	assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+	let trace = String::from_utf8(output.stderr).unwrap();
+	assert!(trace.contains(".audio_data @ 0x"), "{trace}");
+	assert!(trace.contains("ptr @ 0x"), "{trace}");
+}
+
+#[test]
+fn coverage() {
+	let output = run(&["coverage", "../gw2-pf/tests/res/2788751.sound"]);
+	assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+	let stdout = String::from_utf8(output.stdout).unwrap();
+	assert!(stdout.contains("chunk ASND v2 0x1c..0xe94: 4 unread bytes"), "{stdout}");
+	assert!(stdout.contains("0x68..0x6c (4 bytes)"), "{stdout}");
+}
+
+#[test]
+fn export_voices() {
+	let dir = temp_dir("voices");
+	let path = dir.join("198300.csv");
+	let output = run(&["export-voices", "../gw2-pf/tests/res/198300.txtv", path.to_str().unwrap()]);
+	assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+	let csv = std::fs::read_to_string(&path).unwrap();
+	_ = std::fs::remove_dir_all(&dir);
+
+	let data = std::fs::read("../gw2-pf/tests/res/198300.txtv").unwrap();
+	let mappings : usize = gw2_pf_rs::pf::PackFileReader::<gw2_pf_rs::formats::txtv>::from_bytes(&data).map_err(|e| e.to_string()).unwrap()
+		.map(|c| c.map_err(|e| e.to_string()).unwrap().mappings.len()).sum();
+	assert_eq!(csv.lines().next(), Some("textId;voiceId"));
+	assert_eq!(csv.lines().count(), mappings + 1);
+}
+
+#[test]
+fn extract_audio() {
+	let dir = temp_dir("audio");
+	let output = run(&["extract-audio", "../gw2-pf/tests/res/179764.abnk", dir.to_str().unwrap()]);
+	assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+	let files = std::fs::read_dir(&dir).unwrap().count();
+	_ = std::fs::remove_dir_all(&dir);
+	assert!(files > 0);
+}
+
+#[test]
+fn extract_audio_skips_broken_entries() {
+	let dir = temp_dir("audio-broken");
+	let mut data = std::fs::read("../gw2-pf/tests/res/179764.abnk").unwrap();
+	// break the header of the second nested ASND file
+	let nested = (0..data.len() - 12).filter(|i| &data[*i..*i + 2] == b"PF" && &data[*i + 8..*i + 12] == b"ASND").collect::<Vec<_>>();
+	data[nested[1]..nested[1] + 2].copy_from_slice(b"XX");
+	let path = dir.join("broken.abnk");
+	std::fs::write(&path, data).unwrap();
+
+	let output = run(&["extract-audio", path.to_str().unwrap(), dir.join("out").to_str().unwrap()]);
+	let files = std::fs::read_dir(dir.join("out")).unwrap().count();
+	_ = std::fs::remove_dir_all(&dir);
+	let stderr = String::from_utf8_lossy(&output.stderr);
+	assert!(!output.status.success());
+	assert!(stderr.contains("1 entries could not be read"), "{stderr}");
+	assert_eq!(files, 9);
+}
+
+#[test]
+fn extract_audio_metadata() {
+	let dir = temp_dir("audio-metadata");
+	let output = run(&["extract-audio", "--metadata", "../gw2-pf/tests/res/179764.abnk", dir.to_str().unwrap()]);
+	assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+	let mp3 = std::fs::read(dir.join("14940_0.mp3")).unwrap();
+	let json = std::fs::read_to_string(dir.join("14940_0.json")).unwrap();
+	_ = std::fs::remove_dir_all(&dir);
+	assert!(mp3.starts_with(b"ID3"));
+	let json : serde_json::Value = serde_json::from_str(&json).unwrap();
+	assert_eq!(json["voice_id"], 14940);
+	assert_eq!(json["loop_length"], serde_json::Value::Null);
+}
+
+/// `2788751.sound` with its waveforms changed by `edit`.
+fn edited_sound<'a>(data : &'a [u8], edit : impl Fn(&mut WaveformData<'a>)) -> Vec<u8> {
+	let mut writer = gw2_pf_rs::pf::PackFileWriter::<formats::ASND>::new(u16::from_le_bytes([data[2], data[3]]));
+	for chunk in gw2_pf_rs::pf::PackFileReader::<formats::ASND>::from_bytes(data).map_err(|e| e.to_string()).unwrap() {
+		let mut chunk = chunk.map_err(|e| e.to_string()).unwrap();
+		let formats::ASND::ASND(asnd::ASND::V2(waveform)) = &mut chunk;
+		edit(waveform);
+		writer.write_chunk(&chunk).map_err(|e| e.to_string()).unwrap();
+	}
+	writer.finish()
+}
+
+#[test]
+fn extract_audio_loop_metadata() {
+	let data = std::fs::read("../gw2-pf/tests/res/2788751.sound").unwrap();
+	let dir = temp_dir("audio-loop");
+	let path = dir.join("loop.sound");
+	std::fs::write(&path, edited_sound(&data, |waveform| (waveform.loop_start, waveform.loop_end) = (100, 1100))).unwrap();
+
+	let output = run(&["extract-audio", "--metadata", path.to_str().unwrap(), dir.to_str().unwrap()]);
+	assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+	let json = std::fs::read_to_string(dir.join("loop_0.json")).unwrap();
+	_ = std::fs::remove_dir_all(&dir);
+	let json : serde_json::Value = serde_json::from_str(&json).unwrap();
+	assert_eq!((&json["loop_start"], &json["loop_end"], &json["loop_length"]), (&100.into(), &1100.into(), &1000.into()));
+}
+
+#[test]
+fn extract_audio_as_wav() {
+	// replace the mp3 payload with 16 bit stereo samples
+	let data = std::fs::read("../gw2-pf/tests/res/2788751.sound").unwrap();
+	let samples = (0..4800u32 * 2).flat_map(|i| (i as i16).to_le_bytes()).collect::<Vec<_>>();
+	let sound = edited_sound(&data, |waveform| {
+		waveform.audio_data = &samples;
+		waveform.crc = 0;
+		waveform.num_samples = 4800;
+		waveform.num_channels = 2;
+		waveform.length = 0.1;
+	});
+
+	let dir = temp_dir("audio-wav");
+	let path = dir.join("pcm.sound");
+	std::fs::write(&path, sound).unwrap();
+
+	let output = run(&["extract-audio", path.to_str().unwrap(), dir.join("raw").to_str().unwrap()]);
+	assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+	let output = run(&["extract-audio", "--as-wav", path.to_str().unwrap(), dir.join("wav").to_str().unwrap()]);
+	assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+	let raw = std::fs::read(dir.join("raw/pcm_0.bin")).unwrap();
+	let wav = std::fs::read(dir.join("wav/pcm_0.wav")).unwrap();
+	_ = std::fs::remove_dir_all(&dir);
+	assert_eq!(raw, samples);
+	assert_eq!(&wav[..4], b"RIFF");
+	assert!(wav.ends_with(&samples));
+}
+
+#[test]
+fn reject_invalid_arguments() {
+	assert!(!run(&["frobnicate"]).status.success());
+	assert!(!run(&["extract-audio", "../gw2-pf/tests/res/179764.abnk"]).status.success());
+	assert!(!run(&["dump", "does/not/exist.abnk"]).status.success());
+	assert!(!run(&["export-voices", "../gw2-pf/tests/res/179764.abnk", "/dev/null"]).status.success());
+}
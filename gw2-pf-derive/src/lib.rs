@@ -1,10 +1,10 @@
 use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
-use syn::{parse::Parse, parse_macro_input, punctuated::Punctuated, spanned::Spanned, DeriveInput, Fields};
+use syn::{ext::IdentExt, parse::Parse, parse_macro_input, punctuated::Punctuated, spanned::Spanned, DeriveInput, Fields};
 
 
 
-#[proc_macro_derive(Parse, attributes(chunk, v, packfile, null_terminated))]
+#[proc_macro_derive(Parse, attributes(chunk, v, packfile, variant, null_terminated, small))]
 pub fn derive_parse(input : TokenStream) -> TokenStream {
 	let DeriveInput { ident: root_ident, data, attrs, generics: root_generics, .. } = parse_macro_input!(input);
 
@@ -45,9 +45,15 @@ pub fn derive_parse(input : TokenStream) -> TokenStream {
 					else if meta.is_ident("packfile") {
 						err.extend(syn::Error::new(meta.span(), "packfile is only valid for enums").into_compile_error())
 					}
+					else if meta.is_ident("variant") {
+						err.extend(syn::Error::new(meta.span(), "variant is only valid for enums").into_compile_error())
+					}
 					else if meta.is_ident("null_terminated") {
 						err.extend(syn::Error::new(meta.span(), "null_terminated is only valid for struct members").into_compile_error())
 					}
+					else if meta.is_ident("small") {
+						err.extend(syn::Error::new(meta.span(), "small is only valid for struct members").into_compile_error())
+					}
 				}
 			}
 
@@ -59,11 +65,16 @@ pub fn derive_parse(input : TokenStream) -> TokenStream {
 			for field in &fields.named {
 				let _type = &field.ty;
 				let span = field.ty.span();
-				// todo strip lifetimes instead of wraping in triangle brackets
-				sizes.push(quote_spanned!(span => <#_type>::BINARY_SIZE));
+				if field.attrs.iter().any(|attr| attr.meta.path().is_ident("small")) {
+					sizes.push(quote_spanned!(span => crate::parse::SMALL_VEC_BINARY_SIZE));
+				}
+				else {
+					// todo strip lifetimes instead of wraping in triangle brackets
+					sizes.push(quote_spanned!(span => <#_type>::BINARY_SIZE));
+				}
 			}
 			let sizes = match sizes.len() {
-				0 => proc_macro2::TokenStream::new(),
+				0 => quote!{ crate::parse::BinarySize::fixed(0) },
 				1 => sizes.into_iter().next().unwrap(),
 				_ => {
 					let first = &sizes[0];
@@ -72,18 +83,34 @@ pub fn derive_parse(input : TokenStream) -> TokenStream {
 				}
 			};
 
-			let root_ident_str = root_ident.to_string();
+			let serialize_fields = fields.named.iter().map(|f| {
+				let ident = f.ident.as_ref().unwrap();
+				let span = f.ty.span();
+
+				if f.attrs.iter().any(|attr| attr.meta.path().is_ident("null_terminated")) {
+					quote_spanned!(span => crate::serialize::serialize_null_terminated_vec(&self.#ident, output)?;)
+				}
+				else if f.attrs.iter().any(|attr| attr.meta.path().is_ident("small")) {
+					quote_spanned!(span => crate::serialize::serialize_small_vec(&self.#ident, output)?;)
+				}
+				else {
+					quote_spanned!(span => Serialize::serialize(&self.#ident, output)?;)
+				}
+			});
 
 			let fields = fields.named.iter().map(|f| {
 				let ident = f.ident.as_ref().unwrap();
-				let ident_str = ident.to_string();
+				let ident_str = ident.unraw().to_string();
 				let span = f.ty.span();
 
 				if f.attrs.iter().any(|attr| attr.meta.path().is_ident("null_terminated")) {
-					quote_spanned!(span => #ident: { #[cfg(feature = "debug-parsing")] eprintln!("entering {}.{} {:x?}", #root_ident_str, #ident_str, &input.remaining[..std::cmp::min(input.remaining.len(), 8)]); crate::parse::parse_null_terminated_vec(input)? })
+					quote_spanned!(span => #ident: input.parse_field(#ident_str, crate::parse::parse_null_terminated_vec)?)
+				}
+				else if f.attrs.iter().any(|attr| attr.meta.path().is_ident("small")) {
+					quote_spanned!(span => #ident: input.parse_field(#ident_str, crate::parse::parse_small_vec)?)
 				}
 				else {
-					quote_spanned!(span => #ident: { #[cfg(feature = "debug-parsing")] eprintln!("entering {}.{} {:x?}", #root_ident_str, #ident_str, &input.remaining[..std::cmp::min(input.remaining.len(), 8)]); Parse::parse(input)? })
+					quote_spanned!(span => #ident: input.parse_field(#ident_str, Parse::parse)?)
 				}
 			});
 
@@ -92,12 +119,20 @@ pub fn derive_parse(input : TokenStream) -> TokenStream {
 				#[automatically_derived]
 				impl #input_lt crate::parse::Parse #input_lt for #root_ident #root_generics {
 					const BINARY_SIZE : crate::parse::BinarySize = #sizes;
-					fn parse(input : &mut crate::parse::Input #input_lt) -> Result<Self, crate::parse::Error> {
-						#[cfg(feature = "debug-parsing")] eprintln!("[[begin parsing {}, is64: {}]]", #root_ident_str, input.is_64_bit);
+					fn parse(input : &mut crate::parse::Input #input_lt) -> crate::parse::Result<Self> {
 						use crate::parse::Parse;
-						Ok(Self {
+						input.parse_type(std::any::type_name::<Self>(), |input| Ok(Self {
 							#(#fields),*
-						})
+						}))
+					}
+				}
+
+				#[automatically_derived]
+				impl #root_generics crate::serialize::Serialize for #root_ident #root_generics {
+					fn serialize(&self, output : &mut crate::serialize::Output) -> crate::parse::Result<()> {
+						use crate::serialize::Serialize;
+						#(#serialize_fields)*
+						Ok(())
 					}
 				}
 
@@ -121,7 +156,7 @@ pub fn derive_parse(input : TokenStream) -> TokenStream {
 								_ => todo!(),
 							};
 							let span = tuple_field.span();
-							Some(quote_spanned!(span => #version => { #[cfg(feature = "debug-parsing")] eprintln!("entering {} {:x?}", #ident_str, &input.remaining[..std::cmp::min(input.remaining.len(), 8)]); Parse::parse(input).map(Self::#field_ident) }))
+							Some(quote_spanned!(span => #version => input.parse_field(#ident_str, |input| Parse::parse(input).map(Self::#field_ident))))
 						}
 						else{
 							result.extend(syn::Error::new(field_ident.span(), "missing version attribute, add `#[v(..)]`").to_compile_error());
@@ -129,6 +164,15 @@ pub fn derive_parse(input : TokenStream) -> TokenStream {
 						}
 					});
 
+					let versioned_variants = _enum.variants.iter().filter_map(|f| {
+						let field_ident = &f.ident;
+						let version_attr = f.attrs.iter().find(|attr| matches!(attr.meta, syn::Meta::List(ref meta) if meta.path.is_ident("v")))?;
+						let version = version_attr.parse_args_with(syn::LitInt::parse).unwrap();
+						Some((field_ident, version))
+					});
+					let version_arms = versioned_variants.clone().map(|(field_ident, version)| quote!{ Self::#field_ident(_) => #version });
+					let serialize_arms = versioned_variants.map(|(field_ident, _)| quote!{ Self::#field_ident(ref v) => crate::serialize::Serialize::serialize(v, output) });
+
 					let own_magic = syn::LitByteStr::new(root_ident.to_string().as_bytes(), root_ident.span());
 					let root_ident_str = root_ident.to_string();
 
@@ -137,19 +181,17 @@ pub fn derive_parse(input : TokenStream) -> TokenStream {
 						impl #input_lt crate::parse::ParseVersioned #input_lt for #root_ident #root_generics {
 							type Output = Self;
 							fn parse(version : u16, input : &mut crate::parse::Input #input_lt) -> crate::parse::Result<Self::Output> {
-								#[cfg(feature = "debug-parsing")] eprintln!("[[begin parsing {}]]", #root_ident_str);
 								use crate::parse::Parse;
-								match version {
+								input.parse_field(#root_ident_str, |input| match version {
 									#(#fields),*,
 									_ => Err(crate::parse::Error::UnknownVersion { r#type: std::any::type_name::<#root_ident>(), actual: version }),
-								}
+								})
 							}
 						}
 
 						#[automatically_derived]
 						impl #input_lt crate::parse::ParseMagicVariant #input_lt for #root_ident #root_generics {
 							fn parse(magic : u32, version : u16, input : &mut crate::parse::Input #input_lt) -> crate::parse::Result<Self> {
-								#[cfg(feature = "debug-parsing")] eprintln!("[[begin parsing {}]]", #root_ident_str);
 								match magic {
 									<Self as crate::pf::Magic>::MAGIC => <Self as crate::parse::ParseVersioned>::parse(version, input),
 									_ => Err(crate::parse::Error::UnknownMagic { r#type: std::any::type_name::<#root_ident>(), actual: magic }),
@@ -161,6 +203,29 @@ pub fn derive_parse(input : TokenStream) -> TokenStream {
 						impl #root_generics crate::pf::Magic for #root_ident #root_generics {
 							const MAGIC : u32 = crate::fcc(#own_magic);
 						}
+
+						#[automatically_derived]
+						impl #root_generics crate::serialize::SerializeVersioned for #root_ident #root_generics {
+							fn version(&self) -> u16 {
+								match self {
+									#(#version_arms),*
+								}
+							}
+							fn serialize(&self, output : &mut crate::serialize::Output) -> crate::parse::Result<()> {
+								match self {
+									#(#serialize_arms),*
+								}
+							}
+						}
+
+						#[automatically_derived]
+						impl #root_generics crate::serialize::SerializeMagicVariant for #root_ident #root_generics {
+							fn magic(&self) -> u32 { <Self as crate::pf::Magic>::MAGIC }
+							fn version(&self) -> u16 { <Self as crate::serialize::SerializeVersioned>::version(self) }
+							fn serialize(&self, output : &mut crate::serialize::Output) -> crate::parse::Result<()> {
+								<Self as crate::serialize::SerializeVersioned>::serialize(self, output)
+							}
+						}
 					};
 
 					derive_deref_if_only_one_variant(&mut result, &root_ident, &root_generics, &_enum);
@@ -168,7 +233,12 @@ pub fn derive_parse(input : TokenStream) -> TokenStream {
 					break;
 				}
 				else if meta.is_ident("packfile") {
-					let fields = _enum.variants.iter().filter_map(|f| {
+					if let Some(invalid) = _enum.variants.iter().find(|f| !matches!(f.fields, Fields::Unnamed(ref field) if field.unnamed.len() == 1)) {
+						result.extend(syn::Error::new(invalid.span(), "packfile enums must have single-field tuple variants").into_compile_error());
+						break;
+					}
+
+					let fields = _enum.variants.iter().map(|f| {
 						let field_ident = &f.ident;
 						let tuple_field = match f.fields {
 							Fields::Unnamed(ref field) => { &field.unnamed[0] },
@@ -180,10 +250,19 @@ pub fn derive_parse(input : TokenStream) -> TokenStream {
 							_ => todo!(),
 						};
 						let field_type_no_lt = strip_lifetimes_from_path(&field_type.path.segments);
-						Some(quote_spanned!(span => #field_type_no_lt::MAGIC => <#field_type as ParseVersioned>::parse(version, input).map(Self::#field_ident)))
+						quote_spanned!(span => #field_type_no_lt::MAGIC => <#field_type as ParseVersioned>::parse(version, input).map(Self::#field_ident))
+					});
+
+					let variant_types = _enum.variants.iter().map(|f| {
+						let Fields::Unnamed(ref field) = f.fields else { unreachable!() };
+						(&f.ident, &field.unnamed[0].ty)
 					});
+					let magic_arms = variant_types.clone().map(|(field_ident, field_type)| quote!{ Self::#field_ident(_) => <#field_type as crate::pf::Magic>::MAGIC });
+					let version_arms = variant_types.clone().map(|(field_ident, _)| quote!{ Self::#field_ident(ref v) => crate::serialize::SerializeVersioned::version(v) });
+					let serialize_arms = variant_types.map(|(field_ident, _)| quote!{ Self::#field_ident(ref v) => crate::serialize::SerializeVersioned::serialize(v, output) });
 
 					let own_magic = syn::LitByteStr::new(root_ident.to_string().as_bytes(), root_ident.span());
+					let root_ident_str = root_ident.to_string();
 
 					let _impl = quote! {
 						#[automatically_derived]
@@ -196,9 +275,28 @@ pub fn derive_parse(input : TokenStream) -> TokenStream {
 							fn parse(magic : u32, version : u16, input : &mut crate::parse::Input #input_lt) -> crate::parse::Result<Self> {
 								use crate::pf::Magic;
 								use crate::parse::ParseVersioned;
-								match magic {
+								input.parse_field(#root_ident_str, |input| match magic {
 									#(#fields),*,
 									_ => Err(crate::parse::Error::UnknownMagic { r#type: std::any::type_name::<Self>(), actual: magic }),
+								})
+							}
+						}
+
+						#[automatically_derived]
+						impl #root_generics crate::serialize::SerializeMagicVariant for #root_ident #root_generics {
+							fn magic(&self) -> u32 {
+								match self {
+									#(#magic_arms),*
+								}
+							}
+							fn version(&self) -> u16 {
+								match self {
+									#(#version_arms),*
+								}
+							}
+							fn serialize(&self, output : &mut crate::serialize::Output) -> crate::parse::Result<()> {
+								match self {
+									#(#serialize_arms),*
 								}
 							}
 						}
@@ -207,6 +305,54 @@ pub fn derive_parse(input : TokenStream) -> TokenStream {
 
 					derive_deref_if_only_one_variant(&mut result, &root_ident, &root_generics, &_enum);
 
+					break;
+				}
+				else if meta.is_ident("variant") {
+					if let Some(invalid) = _enum.variants.iter().find(|f| !matches!(f.fields, Fields::Unnamed(ref field) if field.unnamed.len() == 1)) {
+						result.extend(syn::Error::new(invalid.span(), "variant enums must have single-field tuple variants").into_compile_error());
+						break;
+					}
+
+					let variants = _enum.variants.iter().enumerate().map(|(index, f)| {
+						let Fields::Unnamed(ref field) = f.fields else { unreachable!() };
+						(index as u32, &f.ident, &field.unnamed[0])
+					});
+
+					let parse_arms = variants.clone().map(|(index, field_ident, tuple_field)| {
+						let ident_str = field_ident.to_string();
+						let span = tuple_field.span();
+						quote_spanned!(span => #index => input.parse_field(#ident_str, |input| Parse::parse(input).map(Self::#field_ident)))
+					});
+					let index_arms = variants.clone().map(|(index, field_ident, _)| quote!{ Self::#field_ident(_) => #index });
+					let serialize_arms = variants.map(|(_, field_ident, _)| quote!{ Self::#field_ident(ref v) => crate::serialize::Serialize::serialize(v, output) });
+
+					result.extend(quote! {
+						#[automatically_derived]
+						impl #input_lt crate::parse::ParseVariant #input_lt for #root_ident #root_generics {
+							fn parse(index : u32, input : &mut crate::parse::Input #input_lt) -> crate::parse::Result<Self> {
+								use crate::parse::Parse;
+								match index {
+									#(#parse_arms,)*
+									_ => Err(crate::parse::Error::UnknownVariant { r#type: std::any::type_name::<Self>(), actual: index }),
+								}
+							}
+						}
+
+						#[automatically_derived]
+						impl #root_generics crate::serialize::SerializeVariant for #root_ident #root_generics {
+							fn index(&self) -> u32 {
+								match self {
+									#(#index_arms),*
+								}
+							}
+							fn serialize(&self, output : &mut crate::serialize::Output) -> crate::parse::Result<()> {
+								match self {
+									#(#serialize_arms),*
+								}
+							}
+						}
+					});
+
 					break;
 				}
 			}
@@ -233,7 +379,7 @@ fn strip_lifetimes_from_path(path : &Path) -> Path {
 					if matches!(generic, syn::GenericArgument::Lifetime(_)) { continue }
 					new_generics.push(generic.clone());
 				}
-				if new_generics.len() == 0 {
+				if new_generics.is_empty() {
 					syn::PathArguments::None
 				}
 				else {
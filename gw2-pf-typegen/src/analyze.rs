@@ -27,21 +27,18 @@ impl<'a> std::iter::Iterator for Parser<'a> {
 			let value = u64::from_le_bytes(chunk.try_into().unwrap());
 			if value & MASK == TARGET {
 				// unfortunately we will catch a lot of special characters with just a mask match, so we filter for ascii chars again
-				const _0 : u8 = '0' as u8;
-				const _9 : u8 = '9' as u8;
-				const _A : u8 = 'A' as u8;
-				const _Z : u8 = 'Z' as u8;
+				const _0 : u8 = b'0';
+				const _9 : u8 = b'9';
+				const _A : u8 = b'A';
+				const _Z : u8 = b'Z';
 				#[allow(non_upper_case_globals)]
-				const _a : u8 = 'a' as u8;
+				const _a : u8 = b'a';
 				#[allow(non_upper_case_globals)]
-				const _z : u8 = 'z' as u8;
-				if (&chunk[..4]).iter().all(|c| matches!(*c, 0 | _0..=_9 | _A..=_Z | _a..=_z)) {
-					match self.parse_chunk(&mut Reader { remaining: self.remaining_bytes }) {
-						Ok((chunk, len)) => {
-							self.remaining_bytes = &self.remaining_bytes[len..];
-							return Some(chunk)
-						},
-						_=> {},
+				const _z : u8 = b'z';
+				if chunk[..4].iter().all(|c| matches!(*c, 0 | _0..=_9 | _A..=_Z | _a..=_z)) {
+					if let Ok((chunk, len)) = self.parse_chunk(&mut Reader { remaining: self.remaining_bytes }) {
+						self.remaining_bytes = &self.remaining_bytes[len..];
+						return Some(chunk)
 					}
 				}
 			}
@@ -82,7 +79,7 @@ impl<'a> Parser<'a> {
 		if versions.is_empty() { return Err(Error::NoChunks) }
 
 		let holds_input_references = versions.iter().any(|c| c.holds_input_references());
-		let chunk = Chunk { magic, versions, holds_input_references };
+		let chunk = Chunk { magic: magic.into(), versions, holds_input_references };
 	
 		Ok((chunk, initial_len - input.remaining.len()))
 	}
@@ -141,7 +138,7 @@ impl<'a> Parser<'a> {
 			"token"    => Type::Token,
 			"char *"   => Type::CString { wide: false },
 			"wchar *"  => Type::CString { wide: true },
-			_ => Type::Composite { name, fields, holds_input_references },
+			_ => Type::Composite { name: name.into(), fields, holds_input_references },
 		})
 	}
 
@@ -215,7 +212,7 @@ impl<'a> Parser<'a> {
 			FT::End => return Ok(FieldParseResult::TypeName(name)),
 		};
 
-		Ok(FieldParseResult::Field(Field { name, _type }))
+		Ok(FieldParseResult::Field(Field { name: name.into(), _type }))
 	}
 }
 
@@ -227,6 +224,7 @@ pub enum FieldParseResult<'a> {
 
 macro_rules! impl_try_from {
 	{ enum $type:ident { $($name:ident = $value:literal),* $(,)? } } => {
+		#[allow(clippy::upper_case_acronyms)]
 		enum $type {
 			$($name = $value),*
 		}
@@ -340,7 +338,7 @@ struct PESection {
 impl<'a> PE<'a> {
 	pub fn parse_header(raw_exe : &'a [u8]) -> Self {
 		let signature_offset = u32::from_le_bytes(raw_exe[0x3c..][..4].try_into().unwrap()) as usize;
-		assert!(&raw_exe[signature_offset..][..4] == &['P' as u8, 'E' as u8, 0, 0]);
+		assert!(raw_exe[signature_offset..][..4] == *b"PE\0\0");
 		let coff_hdr_start = &raw_exe[signature_offset + 4..]; // skip signature to get to the actual header
 
 		let n_sections = u16::from_le_bytes(coff_hdr_start[2..][..2].try_into().unwrap()) as usize;
@@ -375,7 +373,7 @@ impl<'a> PE<'a> {
 	}
 
 	pub fn get_str_at(&self, offset : usize) -> Result<&'a str> {
-		if self.raw_exe.len() <= offset { return Err(Error::OffsetOutOfBounds { offset: offset }); }
+		if self.raw_exe.len() <= offset { return Err(Error::OffsetOutOfBounds { offset }); }
 		let mem = &self.raw_exe[offset..];
 		let mut len = 0;
 		while mem[len] != 0 { len += 1; }
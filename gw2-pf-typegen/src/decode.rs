@@ -0,0 +1,177 @@
+//! Decodes chunk data at runtime using the type definitions from [`crate::analyze`] instead of generated code.
+//! Follows the same layout rules as the `Parse` implementations in `gw2_pf_rs`: pointers are relative to their own position and 0 means null.
+
+use gw2_pf_rs::{parse::{Error, Input, Parse, RawChunk, Result}, CString, FileName, FileRef, Token, WideCString, UUID};
+use crate::structure::{ArrayKind, Chunk, ReferenceKind, Type};
+
+#[derive(Debug)]
+pub enum Value<'a> {
+	/// A null pointer.
+	Null,
+	U8(u8),
+	U16(u16),
+	U32(u32),
+	U64(u64),
+	F32(f32),
+	F64(f64),
+	FileName(FileName),
+	FileRef(FileRef),
+	Token(Token),
+	UUID(UUID),
+	String(String),
+	/// Arrays of bytes are not copied but reference the input.
+	Bytes(&'a [u8]),
+	List(Vec<Value<'a>>),
+	Variant { index : u32, value : Box<Value<'a>> },
+	Struct(Vec<(String, Value<'a>)>),
+}
+
+impl<'a> Value<'a> {
+	/// Looks up a field of a [`Value::Struct`].
+	pub fn get(&self, field : &str) -> Option<&Value<'a>> {
+		match self {
+			Value::Struct(fields) => fields.iter().find(|(name, _)| name == field).map(|(_, value)| value),
+			_ => None,
+		}
+	}
+
+	/// Looks up an element of a [`Value::List`].
+	pub fn index(&self, index : usize) -> Option<&Value<'a>> {
+		match self {
+			Value::List(elements) => elements.get(index),
+			_ => None,
+		}
+	}
+
+	pub fn as_u64(&self) -> Option<u64> {
+		match *self {
+			Value::U8(v)  => Some(v as u64),
+			Value::U16(v) => Some(v as u64),
+			Value::U32(v) => Some(v as u64),
+			Value::U64(v) => Some(v),
+			Value::Token(v) => Some(v.0),
+			_ => None,
+		}
+	}
+
+	pub fn as_f64(&self) -> Option<f64> {
+		match *self {
+			Value::F32(v) => Some(v as f64),
+			Value::F64(v) => Some(v),
+			_ => None,
+		}
+	}
+}
+
+/// Returns the magic of `chunk` in the same representation as [`gw2_pf_rs::pf::ChunkHeader::magic`].
+pub fn chunk_magic(chunk : &Chunk) -> u32 {
+	let mut bytes = [0; 4];
+	for (dst, src) in bytes.iter_mut().zip(chunk.magic.bytes()) { *dst = src }
+	u32::from_le_bytes(bytes)
+}
+
+/// Decodes `raw` using the matching chunk definition out of `chunks`.
+pub fn decode_raw_chunk<'a>(chunks : &[Chunk], raw : &RawChunk<'a>) -> Result<Value<'a>> {
+	let chunk = chunks.iter().find(|c| chunk_magic(c) == raw.magic())
+		.ok_or(Error::UnknownMagic { r#type: "chunk", actual: raw.magic() })?;
+	decode_chunk(chunk, raw.version() as u32, &mut raw.input())
+}
+
+pub fn decode_chunk<'a>(chunk : &Chunk, version : u32, input : &mut Input<'a>) -> Result<Value<'a>> {
+	let version = chunk.versions.iter().find(|v| v.version == version)
+		.ok_or(Error::UnknownVersion { r#type: "chunk", actual: version as u16 })?;
+	decode(&version.root, input)
+}
+
+pub fn decode<'a>(_type : &Type, input : &mut Input<'a>) -> Result<Value<'a>> {
+	Ok(match _type {
+		Type::U8  => Value::U8(u8::parse(input)?),
+		Type::U16 => Value::U16(u16::parse(input)?),
+		Type::U32 => Value::U32(u32::parse(input)?),
+		Type::U64 => Value::U64(u64::parse(input)?),
+		Type::F32 => Value::F32(f32::parse(input)?),
+		Type::F64 => Value::F64(f64::parse(input)?),
+		Type::FileName => match Option::<FileName>::parse(input)? {
+			Some(file_name) => Value::FileName(file_name),
+			None => Value::Null,
+		},
+		Type::FileRef => Value::FileRef(FileRef::parse(input)?),
+		Type::Token => Value::Token(Token::parse(input)?),
+		Type::UUID => Value::UUID(UUID::parse(input)?),
+		Type::CString { wide: false } => {
+			let string = CString::parse(input)?;
+			if string.is_null() { return Ok(Value::Null) }
+			input.reserve_array::<u8>(string.as_bytes().len())?;
+			Value::String(string.to_string_lossy().into_owned())
+		},
+		Type::CString { wide: true } => {
+			let Some(string) = WideCString::parse(input)?.as_wstr() else { return Ok(Value::Null) };
+			input.reserve_array::<u16>(string.len())?;
+			Value::String(string.to_string_lossy())
+		},
+		Type::Reference { kind: ReferenceKind::Optional, inner } => {
+			let Some(offset) = input.eat_offset()? else { return Ok(Value::Null) };
+			decode(inner, &mut input.clone_with_offset(offset)?)?
+		},
+		Type::Reference { kind: ReferenceKind::Inline | ReferenceKind::StructCommon, inner } => decode(inner, input)?,
+		Type::Array { kind, inner } => {
+			match kind {
+				ArrayKind::Inline { size } | ArrayKind::Fixed { size } => decode_elements(inner, *size, input)?,
+				ArrayKind::Dynamic { .. } | ArrayKind::DynamicSmall { .. } => {
+					let length = match kind {
+						ArrayKind::DynamicSmall { .. } => u16::parse(input)? as usize,
+						_ => u32::parse(input)? as usize,
+					};
+					let offset = input.eat_offset()?;
+					if length == 0 { return Ok(Value::List(Vec::new())) }
+					decode_elements(inner, length, &mut input.clone_with_array_offset(offset)?)?
+				},
+				ArrayKind::Pointers { .. } => {
+					let length = u32::parse(input)? as usize;
+					let offset = input.eat_offset()?;
+					if length == 0 { return Ok(Value::List(Vec::new())) }
+					let pointers = &mut input.clone_with_array_offset(offset)?;
+					pointers.reserve_array::<Value>(length)?;
+					let mut elements = Vec::with_capacity(std::cmp::min(length, pointers.remaining.len()));
+					for _ in 0..length {
+						elements.push(match pointers.eat_offset()? {
+							Some(offset) => decode(inner, &mut pointers.clone_with_offset(offset)?)?,
+							None => Value::Null,
+						});
+					}
+					Value::List(elements)
+				},
+			}
+		},
+		Type::Variant { variants, .. } => {
+			let index = u32::parse(input)?;
+			let Some(offset) = input.eat_offset()? else { return Ok(Value::Null) };
+			let inner = variants.get(index as usize).ok_or(Error::UnknownVariant { r#type: "variant", actual: index })?;
+			Value::Variant { index, value: Box::new(decode(inner, &mut input.clone_with_offset(offset)?)?) }
+		},
+		Type::Composite { fields, .. } => {
+			let mut values = Vec::with_capacity(fields.len());
+			for field in fields {
+				values.push((field.name.to_string(), decode(field, input)?));
+			}
+			Value::Struct(values)
+		},
+	})
+}
+
+fn decode_elements<'a>(inner : &Type, length : usize, input : &mut Input<'a>) -> Result<Value<'a>> {
+	if matches!(inner, Type::U8) {
+		if input.remaining.len() < length { return Err(Error::DataTooShort { r#type: Some("byte array"), required: length, actual: input.remaining.len() }) }
+		let bytes = &input.remaining[..length];
+		input.remaining = &input.remaining[length..];
+		return Ok(Value::Bytes(bytes))
+	}
+
+	// the length comes from the input, don't trust it for the allocation
+	input.reserve_array::<Value>(length)?;
+	let mut elements = Vec::with_capacity(std::cmp::min(length, input.remaining.len()));
+	for _ in 0..length {
+		elements.push(decode(inner, input)?);
+	}
+	Ok(Value::List(elements))
+}
@@ -0,0 +1,200 @@
+//! Compares the chunk definitions of two game builds.
+//!
+//! Versions that exist in both builds are compared with each other, new versions are compared against the highest older version of the old build.
+//! Type changes are reported with a path to the changed location, e.g. `bankLanguage[].bankFileName`.
+
+use std::fmt::{Display, Formatter, Result as FmtResult, Write};
+use crate::structure::{ArrayKind, Chunk, ReferenceKind, Type};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Change<'a> {
+	ChunkAdded     { magic : &'a str },
+	ChunkRemoved   { magic : &'a str },
+	VersionAdded   { magic : &'a str, version : u32 },
+	VersionRemoved { magic : &'a str, version : u32 },
+	TypeChanged    { magic : &'a str, old_version : u32, new_version : u32, path : String, change : TypeChange<'a> },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum TypeChange<'a> {
+	FieldAdded           { name : &'a str, _type : &'a Type<'a> },
+	FieldRemoved         { name : &'a str, _type : &'a Type<'a> },
+	/// The type at the path was replaced by an unrelated one.
+	Retyped              { old : &'a Type<'a>, new : &'a Type<'a> },
+	Renamed              { old : &'a str, new : &'a str },
+	ArrayKindChanged     { old : &'a ArrayKind, new : &'a ArrayKind },
+	ReferenceKindChanged { old : &'a ReferenceKind, new : &'a ReferenceKind },
+	VariantAdded         { index : usize, _type : &'a Type<'a> },
+	VariantRemoved       { index : usize, _type : &'a Type<'a> },
+}
+
+pub fn diff<'a>(old : &'a [Chunk<'a>], new : &'a [Chunk<'a>]) -> Vec<Change<'a>> {
+	let mut changes = Vec::new();
+
+	for old_chunk in old {
+		if !new.iter().any(|c| c.magic == old_chunk.magic) {
+			changes.push(Change::ChunkRemoved { magic: &old_chunk.magic });
+		}
+	}
+
+	for new_chunk in new {
+		let magic = &new_chunk.magic;
+		let Some(old_chunk) = old.iter().find(|c| c.magic == new_chunk.magic) else {
+			changes.push(Change::ChunkAdded { magic });
+			continue
+		};
+
+		for old_version in &old_chunk.versions {
+			if !new_chunk.versions.iter().any(|v| v.version == old_version.version) {
+				changes.push(Change::VersionRemoved { magic, version: old_version.version });
+			}
+		}
+
+		for new_version in &new_chunk.versions {
+			let base = match old_chunk.versions.iter().find(|v| v.version == new_version.version) {
+				Some(same) => Some(same),
+				None => {
+					changes.push(Change::VersionAdded { magic, version: new_version.version });
+					old_chunk.versions.iter().filter(|v| v.version < new_version.version).max_by_key(|v| v.version)
+				},
+			};
+
+			if let Some(base) = base {
+				let mut type_changes = Vec::new();
+				diff_types(&base.root, &new_version.root, &mut String::new(), &mut type_changes);
+				changes.extend(type_changes.into_iter().map(|(path, change)| Change::TypeChanged {
+					magic, old_version: base.version, new_version: new_version.version, path, change,
+				}));
+			}
+		}
+	}
+
+	changes
+}
+
+fn diff_types<'a>(old : &'a Type<'a>, new : &'a Type<'a>, path : &mut String, changes : &mut Vec<(String, TypeChange<'a>)>) {
+	let path_len = path.len();
+	match (old, new) {
+		(Type::Composite { name: old_name, fields: old_fields, .. }, Type::Composite { name: new_name, fields: new_fields, .. }) => {
+			if old_name != new_name {
+				changes.push((path.clone(), TypeChange::Renamed { old: old_name, new: new_name }));
+			}
+			for old_field in old_fields {
+				if !new_fields.iter().any(|f| f.name == old_field.name) {
+					changes.push((path.clone(), TypeChange::FieldRemoved { name: &old_field.name, _type: &old_field._type }));
+				}
+			}
+			for new_field in new_fields {
+				match old_fields.iter().find(|f| f.name == new_field.name) {
+					Some(old_field) => {
+						if !path.is_empty() { path.push('.') }
+						path.push_str(&new_field.name);
+						diff_types(&old_field._type, &new_field._type, path, changes);
+						path.truncate(path_len);
+					},
+					None => changes.push((path.clone(), TypeChange::FieldAdded { name: &new_field.name, _type: &new_field._type })),
+				}
+			}
+		},
+		(Type::Array { kind: old_kind, inner: old_inner }, Type::Array { kind: new_kind, inner: new_inner }) => {
+			if old_kind != new_kind {
+				changes.push((path.clone(), TypeChange::ArrayKindChanged { old: old_kind, new: new_kind }));
+			}
+			path.push_str("[]");
+			diff_types(old_inner, new_inner, path, changes);
+			path.truncate(path_len);
+		},
+		(Type::Reference { kind: old_kind, inner: old_inner }, Type::Reference { kind: new_kind, inner: new_inner }) => {
+			if old_kind != new_kind {
+				changes.push((path.clone(), TypeChange::ReferenceKindChanged { old: old_kind, new: new_kind }));
+			}
+			diff_types(old_inner, new_inner, path, changes);
+		},
+		(Type::Variant { variants: old_variants, .. }, Type::Variant { variants: new_variants, .. }) => {
+			for (index, (old_variant, new_variant)) in old_variants.iter().zip(new_variants).enumerate() {
+				write!(path, "<{index}>").unwrap();
+				diff_types(old_variant, new_variant, path, changes);
+				path.truncate(path_len);
+			}
+			for (index, _type) in old_variants.iter().enumerate().skip(new_variants.len()) {
+				changes.push((path.clone(), TypeChange::VariantRemoved { index, _type }));
+			}
+			for (index, _type) in new_variants.iter().enumerate().skip(old_variants.len()) {
+				changes.push((path.clone(), TypeChange::VariantAdded { index, _type }));
+			}
+		},
+		(old, new) => {
+			if old != new {
+				changes.push((path.clone(), TypeChange::Retyped { old, new }));
+			}
+		},
+	}
+}
+
+/// Formats `changes` one per line.
+pub fn report(changes : &[Change]) -> String {
+	let mut report = String::new();
+	for change in changes {
+		writeln!(report, "{change}").unwrap();
+	}
+	report
+}
+
+impl Display for Change<'_> {
+	fn fmt(&self, f : &mut Formatter<'_>) -> FmtResult {
+		match self {
+			Change::ChunkAdded { magic } => write!(f, "+ chunk {magic}"),
+			Change::ChunkRemoved { magic } => write!(f, "- chunk {magic}"),
+			Change::VersionAdded { magic, version } => write!(f, "+ {magic} v{version}"),
+			Change::VersionRemoved { magic, version } => write!(f, "- {magic} v{version}"),
+			Change::TypeChanged { magic, old_version, new_version, path, change } => {
+				if old_version == new_version { write!(f, "~ {magic} v{new_version}")?; }
+				else { write!(f, "~ {magic} v{old_version} -> v{new_version}")?; }
+				let path = if path.is_empty() { "<root>" } else { path };
+				write!(f, " {path}: {change}")
+			},
+		}
+	}
+}
+
+impl Display for TypeChange<'_> {
+	fn fmt(&self, f : &mut Formatter<'_>) -> FmtResult {
+		match self {
+			TypeChange::FieldAdded { name, _type } => write!(f, "field added: {name} : {}", TypeName(_type)),
+			TypeChange::FieldRemoved { name, _type } => write!(f, "field removed: {name} : {}", TypeName(_type)),
+			TypeChange::Retyped { old, new } => write!(f, "type changed: {} -> {}", TypeName(old), TypeName(new)),
+			TypeChange::Renamed { old, new } => write!(f, "struct renamed: {old} -> {new}"),
+			TypeChange::ArrayKindChanged { old, new } => write!(f, "array kind changed: {old:?} -> {new:?}"),
+			TypeChange::ReferenceKindChanged { old, new } => write!(f, "reference kind changed: {old:?} -> {new:?}"),
+			TypeChange::VariantAdded { index, _type } => write!(f, "variant added: {index} : {}", TypeName(_type)),
+			TypeChange::VariantRemoved { index, _type } => write!(f, "variant removed: {index} : {}", TypeName(_type)),
+		}
+	}
+}
+
+/// Short single line description of a type.
+struct TypeName<'a, 'b>(&'b Type<'a>);
+
+impl Display for TypeName<'_, '_> {
+	fn fmt(&self, f : &mut Formatter<'_>) -> FmtResult {
+		match self.0 {
+			Type::U8       => f.write_str("u8"),
+			Type::U16      => f.write_str("u16"),
+			Type::U32      => f.write_str("u32"),
+			Type::U64      => f.write_str("u64"),
+			Type::F32      => f.write_str("f32"),
+			Type::F64      => f.write_str("f64"),
+			Type::FileName => f.write_str("filename"),
+			Type::FileRef  => f.write_str("fileref"),
+			Type::Token    => f.write_str("token"),
+			Type::UUID     => f.write_str("uuid"),
+			Type::CString { wide: false } => f.write_str("cstring"),
+			Type::CString { wide: true  } => f.write_str("wcstring"),
+			Type::Reference { kind: ReferenceKind::Optional, inner } => write!(f, "optional {}", TypeName(inner)),
+			Type::Reference { inner, .. } => TypeName(inner).fmt(f),
+			Type::Array { kind, inner } => write!(f, "{kind:?} array of {}", TypeName(inner)),
+			Type::Variant { variants, .. } => write!(f, "variant of {}", variants.len()),
+			Type::Composite { name, .. } => write!(f, "struct {name}"),
+		}
+	}
+}
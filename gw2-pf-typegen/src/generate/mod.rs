@@ -1,2 +1,95 @@
 pub mod rust;
 pub mod odin;
+
+use std::{fmt::{Display, Formatter, Result as FmtResult}, path::{Path, PathBuf}};
+use crate::{structure::Chunk, Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+	Rust,
+	Odin,
+}
+
+impl std::str::FromStr for Language {
+	type Err = String;
+	fn from_str(s : &str) -> std::result::Result<Self, Self::Err> {
+		match s {
+			"rust" | "rs" => Ok(Language::Rust),
+			"odin"        => Ok(Language::Odin),
+			other => Err(format!("unknown language '{other}', expected rust or odin")),
+		}
+	}
+}
+
+/// A generated source file, `path` is relative to the output directory.
+#[derive(Debug)]
+pub struct GeneratedFile {
+	pub path     : PathBuf,
+	pub contents : String,
+}
+
+impl GeneratedFile {
+	/// Writes the file below `out_dir`, creating missing directories.
+	/// Returns `false` without writing if the file already exists and `overwrite` is not set.
+	pub fn write(&self, out_dir : &Path, overwrite : bool) -> Result<bool> {
+		let path = out_dir.join(&self.path);
+		if !overwrite && path.exists() { return Ok(false) }
+		if let Some(parent) = path.parent() { std::fs::create_dir_all(parent).map_err(Error::Io)?; }
+		std::fs::write(path, &self.contents).map_err(Error::Io)?;
+		Ok(true)
+	}
+}
+
+/// Generates the files for one chunk, using the same layout as `gw2-pf/src/formats`:
+/// - rust: `<magic>/<magic>/<magic>.rs` and `<magic>/<magic>/v<version>.rs`
+/// - odin: `<magic>/<magic>/<magic>.odin` and `<magic>/<magic>/v<version>/<magic>-v<version>.odin`
+///
+/// The file type of the packfile containing the chunk is not known, so the outer directory is also named after the chunk.
+///
+/// The magic is used in the paths, so only 3 or 4 ascii alphanumeric characters are accepted.
+pub fn chunk_files(chunk : &Chunk, language : Language) -> Result<Vec<GeneratedFile>> {
+	let magic = &chunk.magic;
+	//NOTE(Rennorb): Magics found in the executable may end in a null byte, which is stripped.
+	if !matches!(magic.len(), 3 | 4) || !magic.bytes().all(|c| c.is_ascii_alphanumeric()) {
+		return Err(Error::InvalidChunkMagic { magic: magic.to_string() })
+	}
+	let chunk_dir = PathBuf::from(magic.to_lowercase()).join(magic.to_lowercase());
+
+	let mut files = Vec::with_capacity(chunk.versions.len() + 1);
+	match language {
+		Language::Rust => {
+			files.push(GeneratedFile {
+				path    : chunk_dir.join(format!("{}.rs", magic.to_lowercase())),
+				contents: render(|fmt| rust::export_chunk_module(chunk, fmt)),
+			});
+			for version in &chunk.versions {
+				files.push(GeneratedFile {
+					path    : chunk_dir.join(format!("v{}.rs", version.version)),
+					contents: render(|fmt| rust::export_version_module(version, fmt)),
+				});
+			}
+		},
+		Language::Odin => {
+			files.push(GeneratedFile {
+				path    : chunk_dir.join(format!("{magic}.odin")),
+				contents: render(|fmt| odin::export_chunk_package(chunk, fmt)),
+			});
+			for version in &chunk.versions {
+				files.push(GeneratedFile {
+					path    : chunk_dir.join(format!("v{}", version.version)).join(format!("{magic}-v{}.odin", version.version)),
+					contents: render(|fmt| odin::export_version_package(magic, version, fmt)),
+				});
+			}
+		},
+	}
+	Ok(files)
+}
+
+fn render(export : impl Fn(&mut Formatter) -> FmtResult) -> String {
+	struct Render<F>(F);
+	impl<F : Fn(&mut Formatter) -> FmtResult> Display for Render<F> {
+		fn fmt(&self, fmt : &mut Formatter<'_>) -> FmtResult { (self.0)(fmt) }
+	}
+
+	Render(export).to_string()
+}
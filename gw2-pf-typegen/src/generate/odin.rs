@@ -6,6 +6,76 @@ pub fn export_chunk<'a>(chunk : &Chunk<'a>, fmt : &mut Formatter) -> FmtResult {
 	fmt.write_str("}\n")
 }
 
+/// Contents of `<magic>.odin`: the chunk union and the version dispatching `read` procedure.
+pub fn export_chunk_package<'a>(chunk : &Chunk<'a>, fmt : &mut Formatter) -> FmtResult {
+	fmt.write_fmt(format_args!("package gw2_pf_REPLACE_ME_chunks_{};\n\n", chunk.magic))?;
+	fmt.write_str("import \"base:runtime\"\n")?;
+	fmt.write_str("import common \"../../../../../\"\n")?;
+	fmt.write_str("import pf \"../../../../\"\n")?;
+	for (i, version) in chunk.versions.iter().enumerate() {
+		if i > 0 { fmt.write_str("; ")?; }
+		fmt.write_fmt(format_args!("import \"v{}\"", version.version))?;
+	}
+	fmt.write_str("\n\n")?;
+	fmt.write_fmt(format_args!("Magic := common.fourcc_magic(\"{}\")\n\n", chunk.magic))?;
+
+	export_chunk(chunk, fmt)?;
+
+	fmt.write_str("\n")?;
+
+	for version in &chunk.versions {
+		fmt.write_fmt(format_args!("v{} :: v{}.Chunk\n", version.version, version.version))?;
+	}
+
+	fmt.write_str("\n")?;
+
+	fmt.write_str("read :: proc(reader : ^pf.Reader, version : u32, destination : ^Chunk) -> (err : common.ParserError)\n")?;
+	fmt.write_str("{\n")?;
+	fmt.write_str("\tswitch(version) {\n")?;
+	let has_versions_needing_padding = chunk.versions.iter().any(|v| v.version < 10) && chunk.versions.iter().any(|v| v.version >= 10);
+	for version in &chunk.versions {
+		let vpad = if has_versions_needing_padding && version.version < 10 { " " } else { "" };
+		fmt.write_fmt(format_args!("\t\tcase {vpad}{ver}: chunk : {vpad}v{ver}.Chunk; if {vpad}v{ver}.read(reader, &chunk) {{ destination^ = chunk; return }}\n", ver = version.version))?;
+	}
+	fmt.write_str(r#"		case:
+			return common.UnknownVersion {
+				offset = u64(uintptr(reader.cursor) - uintptr(reader.begin)),
+				actual = version,
+			}
+	}
+
+	return common.OutOfData { offset = u64(uintptr(reader.cursor) - uintptr(reader.begin)) }
+}
+"#)
+}
+
+/// Contents of `v<version>/<magic>-v<version>.odin`: all types reachable from the root and their `read` procedures.
+pub fn export_version_package<'a>(magic : &str, version : &SpecificChunkVersion<'a>, fmt : &mut Formatter) -> FmtResult {
+	fmt.write_fmt(format_args!("package gw2_pf_REPLACE_ME_chunks_{magic}_v{};\n\n", version.version))?;
+	fmt.write_str("import pf \"../../../../../\"\n\n")?;
+	fmt.write_fmt(format_args!("Chunk :: {}\n\n", format_type_name(&version.root)))?;
+
+	let linked_nonprimitive_types = &RecursiveTypeReferences::new_with_seed(version);
+
+	fmt.write_str("read :: proc { ")?;
+	for _type in linked_nonprimitive_types.iter() {
+		fmt.write_fmt(format_args!("read_{}, ", format_type_name(_type)))?;
+	}
+	fmt.write_str("}\n\n")?;
+
+	for _type in linked_nonprimitive_types.iter() {
+		export_type(_type, fmt)?;
+		fmt.write_str("\n")?;
+	}
+
+	for (i, _type) in linked_nonprimitive_types.iter().enumerate() {
+		export_type_parser(_type, fmt)?;
+		if i != linked_nonprimitive_types.len() - 1 { fmt.write_str("\n")?; }
+	}
+
+	Ok(())
+}
+
 pub struct RecursiveTypeReferences<'a, 'b> {
 	already_exported : HashSet<Type<'a>>,
 	queue : Vec<&'b Type<'a>>,
@@ -19,14 +89,13 @@ impl<'a, 'b> RecursiveTypeReferences<'a, 'b> {
 	}
 
 	pub fn len(&self) -> usize { self.queue.len() }
+	pub fn is_empty(&self) -> bool { self.queue.is_empty() }
 
 	fn append_recursive(&mut self, _type : &'b Type<'a>) {
 		match _type {
 				Type::Reference { inner, .. } |
-				Type::Array { inner, .. } => {
-					if !is_primitive_type(inner) {
-						self.append_recursive(inner);
-					}
+				Type::Array { inner, .. } if !is_primitive_type(inner) => {
+					self.append_recursive(inner);
 				}
 				Type::Variant { variants, .. } => {
 					self.append(_type);
@@ -60,7 +129,7 @@ impl<'a, 'b> RecursiveTypeReferences<'a, 'b> {
 }
 
 fn is_primitive_type(_type : &Type) -> bool {
-	match _type {
+	matches!(_type,
 		Type::U8  |
 		Type::U16 |
 		Type::U32 |
@@ -71,21 +140,20 @@ fn is_primitive_type(_type : &Type) -> bool {
 		Type::FileRef |
 		Type::Token |
 		Type::UUID |
-		Type::CString { .. } => true,
-		_ => false,
-	}
+		Type::CString { .. }
+	)
 }
 
 pub fn export_type<'a>(_type : &Type<'a>, fmt : &mut Formatter) -> FmtResult {
 	match _type {
 		Type::Composite { name, fields, .. } => {
-			let longest_name_len = fields.iter().map(|f| format_member_name(f.name).len()).max().unwrap_or(0); 
+			let longest_name_len = fields.iter().map(|f| format_member_name(&f.name).len()).max().unwrap_or(0); 
 
 			fmt.write_str(name)?;
 			fmt.write_str(" :: struct {\n")?;
 			for field in fields.iter() {
 				fmt.write_char('\t')?;
-				let field_name = format_member_name(field.name);
+				let field_name = format_member_name(&field.name);
 				fmt.write_str(&field_name)?;
 				let mut padding = longest_name_len.saturating_sub(field_name.len());
 				while padding > 0 {
@@ -129,16 +197,16 @@ pub fn export_type_parser<'a>(_type : &Type<'a>, fmt : &mut Formatter) -> FmtRes
 	fn format_field_parse_expression<'a>(fmt: &mut Formatter, field: &Field<'a>) -> FmtResult {
 		match &field._type {
 			Type::Array { kind: ArrayKind::Inline { .. }, inner } if inner.is_compact() => {
-				fmt.write_fmt(format_args!("\tpf.read(reader, &destination.{})", format_member_name(field.name)))?;
+				fmt.write_fmt(format_args!("\tpf.read(reader, &destination.{})", format_member_name(&field.name)))?;
 			},
 			Type::Array { inner, .. } if inner.is_compact() => {
-				fmt.write_fmt(format_args!("\tpf.read_slice_packed(reader, &destination.{})", format_member_name(field.name)))?;
+				fmt.write_fmt(format_args!("\tpf.read_slice_packed(reader, &destination.{})", format_member_name(&field.name)))?;
 			},
 			Type::Array { inner, .. } => {
-				fmt.write_fmt(format_args!("\tpf.read(reader, &destination.{}, read_{})", format_member_name(field.name), format_type_name(inner)))?;
+				fmt.write_fmt(format_args!("\tpf.read(reader, &destination.{}, read_{})", format_member_name(&field.name), format_type_name(inner)))?;
 			},
 			_ => {
-				fmt.write_fmt(format_args!("\tpf.read(reader, &destination.{})", format_member_name(field.name)))?;
+				fmt.write_fmt(format_args!("\tpf.read(reader, &destination.{})", format_member_name(&field.name)))?;
 			},
 		}
 		Ok(())
@@ -191,7 +259,7 @@ pub fn export_type_parser<'a>(_type : &Type<'a>, fmt : &mut Formatter) -> FmtRes
 	
 }
 
-pub fn format_type_name<'a>(_type : &Type<'a>) -> Cow<'a, str> {
+pub fn format_type_name<'a>(_type : &'a Type) -> Cow<'a, str> {
 	match _type {
 		Type::U8       => Cow::Borrowed("u8"),
 		Type::U16      => Cow::Borrowed("u16"),
@@ -227,12 +295,11 @@ pub fn format_type_name<'a>(_type : &Type<'a>) -> Cow<'a, str> {
 
 pub fn get_variant_type_name(_variant : &Type) -> String {
 	let Type::Variant { holds_input_references, .. } = _variant else { unreachable!() };
-	let hasher = &mut DefaultHasher::new();
-	_variant.hash(hasher);
 	let lifetime = if *holds_input_references { "<'a>" } else { "" };
-	format!("Variant_{}{lifetime}", hasher.finish())
+	format!("Variant_{}{lifetime}", schema::type_hash(_variant))
 }
 
+#[allow(unreachable_code, unused_variables, clippy::match_single_binding)] // all keywords are currently commented out
 pub fn format_member_name<'a>(raw_name : &'a str) -> Cow<'a, str> {
 	stringify!(type);
 	let reserved = match raw_name {
@@ -313,6 +380,6 @@ pub fn add_required_imports_for_type_recursive<'a>(imports : &mut HashSet<&'a st
 
 
 
-use std::{borrow::Cow, collections::HashSet, fmt::{Formatter, Result as FmtResult, Write}, hash::{DefaultHasher, Hash, Hasher}};
-use crate::structure::{ArrayKind, Chunk, Field, ReferenceKind, Type};
+use std::{borrow::Cow, collections::HashSet, fmt::{Formatter, Result as FmtResult, Write}};
+use crate::{schema, structure::{ArrayKind, Chunk, Field, ReferenceKind, SpecificChunkVersion, Type}};
 
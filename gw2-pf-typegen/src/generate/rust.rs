@@ -1,8 +1,9 @@
 pub fn export_chunk<'a>(chunk : &Chunk<'a>, fmt : &mut Formatter) -> FmtResult {
 	fmt.write_str("#[derive(Debug, crate::Parse)]\n")?;
+	fmt.write_str("#[cfg_attr(feature = \"serde\", derive(serde::Serialize))]\n")?;
 	fmt.write_str("#[chunk]\n")?;
 	fmt.write_str("pub enum ")?;
-	fmt.write_str(chunk.magic)?;
+	fmt.write_str(&chunk.magic)?;
 	if chunk.holds_input_references { fmt.write_str("<'a>")?; }
 	fmt.write_str(" {\n")?;
 	for version in chunk.versions.iter() {
@@ -11,8 +12,39 @@ pub fn export_chunk<'a>(chunk : &Chunk<'a>, fmt : &mut Formatter) -> FmtResult {
 	fmt.write_str("}\n")
 }
 
+/// Contents of `<magic>.rs`: the version module declarations followed by the chunk enum.
+pub fn export_chunk_module<'a>(chunk : &Chunk<'a>, fmt : &mut Formatter) -> FmtResult {
+	for version in &chunk.versions {
+		fmt.write_fmt(format_args!("pub mod v{};\n", version.version))?;
+	}
+	fmt.write_str("\n")?;
+
+	export_chunk(chunk, fmt)
+}
+
+/// Contents of `v<version>.rs`: the required imports followed by all types reachable from the root.
+pub fn export_version_module<'a>(version : &SpecificChunkVersion<'a>, fmt : &mut Formatter) -> FmtResult {
+	let mut imports = HashSet::new();
+	add_required_imports_for_type_recursive(&mut imports, version);
+	let mut imports = imports.into_iter().collect::<Vec<_>>();
+	imports.sort_unstable();
+
+	match imports.as_slice() {
+		[] => {},
+		[import] => fmt.write_fmt(format_args!("use crate::{import};\n\n"))?,
+		imports => fmt.write_fmt(format_args!("use crate::{{{}}};\n\n", imports.join(", ")))?,
+	}
+
+	let linked_nonprimitive_types = &RecursiveTypeReferences::new_with_seed(version);
+	for (i, _type) in linked_nonprimitive_types.into_iter().enumerate() {
+		if i > 0 { fmt.write_str("\n")?; }
+		export_type(_type, fmt)?;
+	}
+	Ok(())
+}
+
 pub struct RecursiveTypeReferences<'a, 'b> {
-	already_exported : HashSet<Type<'a>>,
+	already_exported : HashSet<&'b Type<'a>>,
 	queue : Vec<&'b Type<'a>>,
 }
 
@@ -24,17 +56,16 @@ impl<'a, 'b> RecursiveTypeReferences<'a, 'b> {
 	}
 
 	pub fn len(&self) -> usize { self.queue.len() }
+	pub fn is_empty(&self) -> bool { self.queue.is_empty() }
 
 	fn append_recursive(&mut self, _type : &'b Type<'a>) {
 		match _type {
 				Type::Reference { inner, .. } |
-				Type::Array { inner, .. } => {
-					if !is_primitive_type(inner) {
-						self.append_recursive(inner);
-					}
+				Type::Array { inner, .. } if !is_primitive_type(inner) => {
+					self.append_recursive(inner);
 				}
 				Type::Variant { variants, .. } => {
-					self.append(_type);
+					if !self.append(_type) { return }
 					for inner in variants {
 						if !is_primitive_type(inner) {
 							self.append_recursive(inner);
@@ -42,7 +73,7 @@ impl<'a, 'b> RecursiveTypeReferences<'a, 'b> {
 					}
 				}
 				Type::Composite { fields, .. } =>  {
-					self.append(_type);
+					if !self.append(_type) { return }
 					for field in fields {
 						if !is_primitive_type(field) {
 							self.append_recursive(field);
@@ -53,15 +84,16 @@ impl<'a, 'b> RecursiveTypeReferences<'a, 'b> {
 		}
 	}
 
-	pub fn append(&mut self, _type : &'b Type<'a>) {
-		if !self.already_exported.contains(_type) {
-			self.queue.push(_type);
-		}
+	/// Returns false if the type was already queued.
+	pub fn append(&mut self, _type : &'b Type<'a>) -> bool {
+		if !self.already_exported.insert(_type) { return false }
+		self.queue.push(_type);
+		true
 	}
 }
 
 fn is_primitive_type(_type : &Type) -> bool {
-	match _type {
+	matches!(_type,
 		Type::U8  |
 		Type::U16 |
 		Type::U32 |
@@ -72,9 +104,8 @@ fn is_primitive_type(_type : &Type) -> bool {
 		Type::FileRef |
 		Type::Token |
 		Type::UUID |
-		Type::CString { .. } => true,
-		_ => false,
-	}
+		Type::CString { .. }
+	)
 }
 
 impl<'a, 'b, 'c> std::iter::IntoIterator for &'c RecursiveTypeReferences<'a, 'b> {
@@ -86,16 +117,24 @@ impl<'a, 'b, 'c> std::iter::IntoIterator for &'c RecursiveTypeReferences<'a, 'b>
 pub fn export_type<'a>(_type : &Type<'a>, fmt : &mut Formatter) -> FmtResult {
 	match _type {
 		Type::Composite { name, fields, holds_input_references } => {
-			let longest_name_len = fields.iter().map(|f| format_member_name(f.name).len()).max().unwrap_or(0); 
+			let longest_name_len = fields.iter().map(|f| format_member_name(&f.name).len()).max().unwrap_or(0); 
 
 			fmt.write_str("#[derive(Debug, crate::Parse)]\n")?;
+			fmt.write_str("#[cfg_attr(feature = \"serde\", derive(serde::Serialize))]\n")?;
 			fmt.write_str("pub struct ")?;
 			fmt.write_str(name)?;
 			if *holds_input_references { fmt.write_str("<'a>")?; }
 			fmt.write_str(" {\n")?;
 			for field in fields.iter() {
-				fmt.write_char('\t')?;
-				let field_name = format_member_name(field.name);
+				match field._type {
+					Type::Array { kind: ArrayKind::DynamicSmall { .. }, .. } => fmt.write_str("\t#[small]\n")?,
+					Type::Array { kind: ArrayKind::Inline { size } | ArrayKind::Fixed { size }, .. } if size > MAX_SERDE_ARRAY_LENGTH => {
+						fmt.write_str("\t#[cfg_attr(feature = \"serde\", serde(serialize_with = \"crate::serialize::serde_array\"))]\n")?
+					},
+					_ => {},
+				}
+				fmt.write_str("\tpub ")?;
+				let field_name = format_member_name(&field.name);
 				fmt.write_str(&field_name)?;
 				let mut padding = longest_name_len.saturating_sub(field_name.len());
 				while padding > 0 {
@@ -104,10 +143,7 @@ pub fn export_type<'a>(_type : &Type<'a>, fmt : &mut Formatter) -> FmtResult {
 				}
 				fmt.write_str(" : ")?;
 				fmt.write_str(&format_type_name(field))?;
-				match field._type {
-					Type::Array { kind: ArrayKind::DynamicSmall { .. }, .. } => { fmt.write_str(", // small")? } ,
-					_ => fmt.write_str(",")?,
-				}
+				fmt.write_str(",")?;
 				match field._type {
 					Type::Array { kind: ArrayKind::Dynamic { size }, .. } |
 					Type::Array { kind: ArrayKind::DynamicSmall { size }, .. } |
@@ -122,6 +158,9 @@ pub fn export_type<'a>(_type : &Type<'a>, fmt : &mut Formatter) -> FmtResult {
 
 		Type::Variant { variants, .. } => {
 			fmt.write_str("#[derive(Debug, crate::Parse)]\n")?;
+			fmt.write_str("#[cfg_attr(feature = \"serde\", derive(serde::Serialize))]\n")?;
+			fmt.write_str("#[variant]\n")?;
+			fmt.write_str("#[allow(non_camel_case_types)]\n")?;
 			fmt.write_str("pub enum ")?;
 			fmt.write_str(&get_variant_type_name(_type))?;
 			fmt.write_str(" {\n")?;
@@ -136,7 +175,10 @@ pub fn export_type<'a>(_type : &Type<'a>, fmt : &mut Formatter) -> FmtResult {
 	
 }
 
-fn format_type_name<'a>(_type : &Type<'a>) -> Cow<'a, str> {
+/// `serde` only implements `Serialize` for arrays up to this length.
+const MAX_SERDE_ARRAY_LENGTH : usize = 32;
+
+fn format_type_name<'a>(_type : &'a Type) -> Cow<'a, str> {
 	match _type {
 		Type::U8       => Cow::Borrowed("u8"),
 		Type::U16      => Cow::Borrowed("u16"),
@@ -144,16 +186,17 @@ fn format_type_name<'a>(_type : &Type<'a>) -> Cow<'a, str> {
 		Type::U64      => Cow::Borrowed("u64"),
 		Type::F32      => Cow::Borrowed("f32"),
 		Type::F64      => Cow::Borrowed("f64"),
-		Type::FileName => Cow::Borrowed("FileName"),
+		Type::FileName => Cow::Borrowed("Option<FileName>"), // file names are pointers that may be null
 		Type::FileRef  => Cow::Borrowed("FileRef"),
 		Type::Token    => Cow::Borrowed("Token"),
 		Type::UUID     => Cow::Borrowed("UUID"),
-		Type::CString { wide: false } => Cow::Borrowed("CString"), //turn into annotations?
-		Type::CString { wide: true  } => Cow::Borrowed("WideCString"), //turn into annotations?
+		Type::CString { wide: false } => Cow::Borrowed("CString<'a>"), //turn into annotations?
+		Type::CString { wide: true  } => Cow::Borrowed("WideCString<'a>"), //turn into annotations?
 		Type::Reference { inner, kind: ReferenceKind::Optional } => Cow::Owned(format!("Option<{}>", format_type_name(inner))),
 		Type::Reference { inner, .. } => format_type_name(inner),
-		Type::Array { inner, kind: ArrayKind::Inline { size } } => Cow::Owned(format!("[{}; {size}]", format_type_name(inner))),
+		Type::Array { inner, kind: ArrayKind::Inline { size } | ArrayKind::Fixed { size } } => Cow::Owned(format!("[{}; {size}]", format_type_name(inner))),
 		Type::Array { inner, kind: ArrayKind::Pointers { .. } } => Cow::Owned(format!("Vec<Option<{}>>", format_type_name(inner))), //turn size into annotations?
+		Type::Array { inner, kind: ArrayKind::DynamicSmall { .. } } => Cow::Owned(format!("Vec<{}>", format_type_name(inner))), // marked with #[small]
 		Type::Array { inner, .. } => {
 			match inner.as_ref() {
 				Type::U8 => Cow::Borrowed("&'a [u8]"), //turn size into annotations?
@@ -161,7 +204,7 @@ fn format_type_name<'a>(_type : &Type<'a>) -> Cow<'a, str> {
 			}
 		},
 		Type::Variant { .. } => {
-			Cow::Owned(get_variant_type_name(_type))
+			Cow::Owned(format!("Tagged<{}>", get_variant_type_name(_type)))
 		},
 		Type::Composite { name, holds_input_references, .. } => {
 			if *holds_input_references {
@@ -176,10 +219,8 @@ fn format_type_name<'a>(_type : &Type<'a>) -> Cow<'a, str> {
 
 pub fn get_variant_type_name(_variant : &Type) -> String {
 	let Type::Variant { holds_input_references, .. } = _variant else { unreachable!() };
-	let hasher = &mut DefaultHasher::new();
-	_variant.hash(hasher);
 	let lifetime = if *holds_input_references { "<'a>" } else { "" };
-	format!("Variant_{}{lifetime}", hasher.finish())
+	format!("Variant_{}{lifetime}", schema::type_hash(_variant))
 }
 
 pub fn format_member_name<'a>(raw_name : &'a str) -> Cow<'a, str> {
@@ -254,11 +295,13 @@ pub fn add_required_imports_for_type_recursive<'a>(imports : &mut HashSet<&'a st
 		Type::FileName => { imports.insert("FileName"); },
 		Type::FileRef  => { imports.insert("FileRef"); },
 		Type::Token    => { imports.insert("Token"); },
+		Type::UUID     => { imports.insert("UUID"); },
 		Type::CString { wide: true }  => { imports.insert("WideCString"); },
 		Type::CString { wide: false } => { imports.insert("CString"); },
 		Type::Array { inner, .. } |
 		Type::Reference { inner, .. } => { add_required_imports_for_type_recursive(imports, inner) },
 		Type::Variant { variants, .. } => {
+			imports.insert("Tagged");
 			for variant in variants {
 				add_required_imports_for_type_recursive(imports, variant);
 			}
@@ -274,6 +317,6 @@ pub fn add_required_imports_for_type_recursive<'a>(imports : &mut HashSet<&'a st
 
 
 
-use std::{borrow::Cow, collections::HashSet, fmt::{Formatter, Result as FmtResult, Write}, hash::{DefaultHasher, Hash, Hasher}};
-use crate::structure::{ArrayKind, Chunk, ReferenceKind, Type};
+use std::{borrow::Cow, collections::HashSet, fmt::{Formatter, Result as FmtResult, Write}};
+use crate::{schema, structure::{ArrayKind, Chunk, ReferenceKind, SpecificChunkVersion, Type}};
 
@@ -1,6 +1,9 @@
 pub mod structure;
 pub mod analyze;
 pub mod generate;
+pub mod decode;
+pub mod schema;
+pub mod diff;
 
 
 #[derive(Debug)]
@@ -12,6 +15,11 @@ pub enum Error {
 	DuplicateChunk { magic: [u8; 4], max_version: u32, meta_offset: usize },
 	NoChunks,
 	DecodingFailed,
+	Io(std::io::Error),
+	InvalidSchema { line : usize, expected : &'static str, actual : String },
+	UnsupportedSchemaVersion { actual : usize },
+	InvalidSchemaName { name : String },
+	InvalidChunkMagic { magic : String },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
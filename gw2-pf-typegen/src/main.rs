@@ -0,0 +1,98 @@
+use std::{ffi::OsString, path::PathBuf, process::ExitCode};
+use gw2_pf_typegen::{analyze, generate::{self, Language}, schema, structure::Chunk};
+
+const USAGE : &str = "\
+Usage: gw2-pf-typegen [options] <input> <output dir>
+
+<input> is either the game executable (Gw2-64.exe) or a schema file saved with --save-schema.
+The generated files use the layout of gw2-pf/src/formats: <magic>/<magic>/<magic>.rs and v<version>.rs.
+
+Options:
+  --lang <rust|odin>     Language to generate, defaults to rust.
+  --magic <magic>        Only generate the given chunk, can be repeated.
+  --overwrite            Replace existing files instead of skipping them.
+  --save-schema <file>   Also store the extracted definitions as a schema file.
+";
+
+type Result<T> = std::result::Result<T, String>;
+
+struct Options {
+	input       : PathBuf,
+	output      : PathBuf,
+	language    : Language,
+	magics      : Vec<String>,
+	overwrite   : bool,
+	save_schema : Option<PathBuf>,
+}
+
+fn main() -> ExitCode {
+	let args = std::env::args_os().skip(1).collect::<Vec<_>>();
+	if args.is_empty() || args.iter().any(|a| a == "--help" || a == "-h") {
+		print!("{USAGE}");
+		return ExitCode::SUCCESS
+	}
+
+	match parse_options(args).and_then(run) {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(e) => {
+			eprintln!("error: {e}");
+			ExitCode::FAILURE
+		},
+	}
+}
+
+fn parse_options(args : Vec<OsString>) -> Result<Options> {
+	let mut language = Language::Rust;
+	let mut magics = Vec::new();
+	let mut overwrite = false;
+	let mut save_schema = None;
+	let mut paths = Vec::new();
+
+	let mut args = args.into_iter();
+	while let Some(arg) = args.next() {
+		let mut value = |name : &str| args.next().ok_or_else(|| format!("missing value for {name}\n\n{USAGE}"));
+		match arg.to_str() {
+			Some("--lang") => language = value("--lang")?.to_string_lossy().parse()?,
+			Some("--magic") => magics.push(value("--magic")?.to_string_lossy().into_owned()),
+			Some("--overwrite") => overwrite = true,
+			Some("--save-schema") => save_schema = Some(PathBuf::from(value("--save-schema")?)),
+			Some(option) if option.starts_with("--") => return Err(format!("unknown option {option}\n\n{USAGE}")),
+			_ => paths.push(PathBuf::from(arg)),
+		}
+	}
+
+	let [input, output] = <[PathBuf; 2]>::try_from(paths).map_err(|_| format!("expected an input and an output path\n\n{USAGE}"))?;
+	Ok(Options { input, output, language, magics, overwrite, save_schema })
+}
+
+fn run(options : Options) -> Result<()> {
+	let data = std::fs::read(&options.input).map_err(|e| format!("failed to read {}: {e}", options.input.display()))?;
+
+	let chunks : Vec<Chunk> = if data.starts_with(b"MZ") {
+		analyze::locate_chunks(&data).collect()
+	}
+	else {
+		let text = std::str::from_utf8(&data).map_err(|_| format!("{} is neither an executable nor a schema file", options.input.display()))?;
+		schema::parse(text).map_err(|e| format!("failed to load schema: {e:?}"))?
+	};
+
+	if let Some(path) = &options.save_schema {
+		schema::save(path, &chunks).map_err(|e| format!("failed to save schema: {e:?}"))?;
+	}
+
+	for magic in &options.magics {
+		if !chunks.iter().any(|c| c.magic == *magic) { eprintln!("[warn] No chunk with magic {magic} found."); }
+	}
+
+	for chunk in &chunks {
+		if !options.magics.is_empty() && !options.magics.iter().any(|m| *m == chunk.magic) { continue }
+
+		for file in generate::chunk_files(chunk, options.language).map_err(|e| format!("failed to generate {}: {e:?}", chunk.magic))? {
+			let written = file.write(&options.output, options.overwrite).map_err(|e| format!("failed to write {}: {e:?}", file.path.display()))?;
+			if written { println!("{}", file.path.display()); }
+			else { eprintln!("[warn] {} already exists, skipping write.", file.path.display()); }
+		}
+	}
+
+	Ok(())
+}
@@ -0,0 +1,252 @@
+//! Text format to store extracted chunk definitions, so they can be used without the game executable.
+//!
+//! The file starts with `gw2-pf-schema <format version>` followed by the chunks:
+//! ```text
+//! chunk txtv {
+//!     version 0 struct TextPackVoices {
+//!         mappings array dynamic 0 struct TextPackVoice {
+//!             textId u32
+//!             voiceId u32
+//!         }
+//!     }
+//! }
+//! ```
+//! Tokens are separated by whitespace, indentation and line breaks are only there for readability.
+//! `holds_input_references` is not stored, it is recomputed from the types while loading.
+
+use std::{borrow::Cow, fmt::Write};
+use crate::{structure::{ArrayKind, Chunk, Field, ReferenceKind, SpecificChunkVersion, Type}, Error, Result};
+
+pub const FORMAT_VERSION : u32 = 1;
+const HEADER : &str = "gw2-pf-schema";
+
+pub fn save(path : impl AsRef<std::path::Path>, chunks : &[Chunk]) -> Result<()> {
+	std::fs::write(path, serialize(chunks)?).map_err(Error::Io)
+}
+
+pub fn load(path : impl AsRef<std::path::Path>) -> Result<Vec<Chunk<'static>>> {
+	let text = std::fs::read_to_string(path).map_err(Error::Io)?;
+	Ok(parse(&text)?.into_iter().map(Chunk::into_owned).collect())
+}
+
+pub fn serialize(chunks : &[Chunk]) -> Result<String> {
+	let mut out = String::new();
+	writeln!(out, "{HEADER} {FORMAT_VERSION}").unwrap();
+	for chunk in chunks {
+		validate_name(&chunk.magic)?;
+		writeln!(out, "chunk {} {{", chunk.magic).unwrap();
+		for version in &chunk.versions {
+			write!(out, "\tversion {} ", version.version).unwrap();
+			write_type(&mut out, &version.root, 1)?;
+			out.push('\n');
+		}
+		out.push_str("}\n");
+	}
+	Ok(out)
+}
+
+/// Names end up as identifiers and paths in generated code, so only ascii alphanumeric characters and `_` are allowed.
+fn validate_name(name : &str) -> Result<()> {
+	if name.is_empty() || !name.bytes().all(|c| c.is_ascii_alphanumeric() || c == b'_') {
+		return Err(Error::InvalidSchemaName { name: name.to_string() })
+	}
+	Ok(())
+}
+
+fn write_type(out : &mut String, _type : &Type, indent : usize) -> Result<()> {
+	match _type {
+		Type::U8       => out.push_str("u8"),
+		Type::U16      => out.push_str("u16"),
+		Type::U32      => out.push_str("u32"),
+		Type::U64      => out.push_str("u64"),
+		Type::F32      => out.push_str("f32"),
+		Type::F64      => out.push_str("f64"),
+		Type::FileName => out.push_str("filename"),
+		Type::FileRef  => out.push_str("fileref"),
+		Type::Token    => out.push_str("token"),
+		Type::UUID     => out.push_str("uuid"),
+		Type::CString { wide: false } => out.push_str("cstring"),
+		Type::CString { wide: true  } => out.push_str("wcstring"),
+		Type::Reference { kind, inner } => {
+			out.push_str(match kind {
+				ReferenceKind::Optional     => "optional ",
+				ReferenceKind::Inline       => "inline ",
+				ReferenceKind::StructCommon => "common ",
+			});
+			write_type(out, inner, indent)?;
+		},
+		Type::Array { kind, inner } => {
+			let (kind, size) = match kind {
+				ArrayKind::Dynamic      { size } => ("dynamic", size),
+				ArrayKind::DynamicSmall { size } => ("small", size),
+				ArrayKind::Pointers     { size } => ("pointers", size),
+				ArrayKind::Inline       { size } => ("inline", size),
+				ArrayKind::Fixed        { size } => ("fixed", size),
+			};
+			write!(out, "array {kind} {size} ").unwrap();
+			write_type(out, inner, indent)?;
+		},
+		Type::Variant { variants, .. } => {
+			out.push_str("variant {\n");
+			for variant in variants {
+				push_indent(out, indent + 1);
+				write_type(out, variant, indent + 1)?;
+				out.push('\n');
+			}
+			push_indent(out, indent);
+			out.push('}');
+		},
+		Type::Composite { name, fields, .. } => {
+			validate_name(name)?;
+			writeln!(out, "struct {name} {{").unwrap();
+			for field in fields {
+				validate_name(&field.name)?;
+				push_indent(out, indent + 1);
+				write!(out, "{} ", field.name).unwrap();
+				write_type(out, field, indent + 1)?;
+				out.push('\n');
+			}
+			push_indent(out, indent);
+			out.push('}');
+		},
+	}
+	Ok(())
+}
+
+/// FNV-1a hash of the schema text of `_type`, used to name types that have no name of their own.
+/// Unlike [`std::hash::DefaultHasher`] the result doesn't change between compiler versions.
+pub fn type_hash(_type : &Type) -> u64 {
+	let mut text = String::new();
+	//NOTE(Rennorb): An invalid name only ends the text early, the hash stays deterministic.
+	_ = write_type(&mut text, _type, 0);
+	text.bytes().fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+fn push_indent(out : &mut String, indent : usize) {
+	for _ in 0..indent { out.push('\t') }
+}
+
+/// Parses a schema, the names in the result borrow from `text`.
+pub fn parse(text : &str) -> Result<Vec<Chunk<'_>>> {
+	let tokens = &mut Tokens {
+		tokens   : text.lines().enumerate().flat_map(|(i, line)| line.split_whitespace().map(move |t| (i + 1, t))).peekable(),
+		last_line: 1,
+	};
+
+	tokens.expect(HEADER)?;
+	let version = tokens.number("format version")?;
+	if version != FORMAT_VERSION as usize { return Err(Error::UnsupportedSchemaVersion { actual: version }) }
+
+	let mut chunks = Vec::new();
+	while tokens.peek().is_some() {
+		tokens.expect("chunk")?;
+		let magic = tokens.next("chunk magic")?;
+		validate_name(magic)?;
+		tokens.expect("{")?;
+		let mut versions = Vec::new();
+		while !tokens.eat("}") {
+			tokens.expect("version")?;
+			let version = tokens.number("chunk version")? as u32;
+			let root = parse_type(tokens)?;
+			versions.push(SpecificChunkVersion { version, root });
+		}
+		let holds_input_references = versions.iter().any(|v| v.holds_input_references());
+		chunks.push(Chunk { magic: Cow::Borrowed(magic), holds_input_references, versions });
+	}
+
+	Ok(chunks)
+}
+
+fn parse_type<'a>(tokens : &mut Tokens<'a, impl Iterator<Item = (usize, &'a str)>>) -> Result<Type<'a>> {
+	Ok(match tokens.next("type")? {
+		"u8"       => Type::U8,
+		"u16"      => Type::U16,
+		"u32"      => Type::U32,
+		"u64"      => Type::U64,
+		"f32"      => Type::F32,
+		"f64"      => Type::F64,
+		"filename" => Type::FileName,
+		"fileref"  => Type::FileRef,
+		"token"    => Type::Token,
+		"uuid"     => Type::UUID,
+		"cstring"  => Type::CString { wide: false },
+		"wcstring" => Type::CString { wide: true },
+		"optional" => Type::Reference { kind: ReferenceKind::Optional, inner: Box::new(parse_type(tokens)?) },
+		"inline"   => Type::Reference { kind: ReferenceKind::Inline, inner: Box::new(parse_type(tokens)?) },
+		"common"   => Type::Reference { kind: ReferenceKind::StructCommon, inner: Box::new(parse_type(tokens)?) },
+		"array"    => {
+			let kind = tokens.next("array kind")?;
+			let size = tokens.number("array size")?;
+			let kind = match kind {
+				"dynamic"  => ArrayKind::Dynamic { size },
+				"small"    => ArrayKind::DynamicSmall { size },
+				"pointers" => ArrayKind::Pointers { size },
+				"inline"   => ArrayKind::Inline { size },
+				"fixed"    => ArrayKind::Fixed { size },
+				other => return Err(tokens.unexpected("array kind", other)),
+			};
+			Type::Array { kind, inner: Box::new(parse_type(tokens)?) }
+		},
+		"variant"  => {
+			tokens.expect("{")?;
+			let mut variants = Vec::new();
+			while !tokens.eat("}") {
+				variants.push(parse_type(tokens)?);
+			}
+			let holds_input_references = variants.iter().any(|v| v.holds_input_references());
+			Type::Variant { variants, holds_input_references }
+		},
+		"struct"   => {
+			let name = tokens.next("struct name")?;
+			validate_name(name)?;
+			tokens.expect("{")?;
+			let mut fields = Vec::new();
+			while !tokens.eat("}") {
+				let name = tokens.next("field name")?;
+				validate_name(name)?;
+				fields.push(Field { name: Cow::Borrowed(name), _type: parse_type(tokens)? });
+			}
+			let holds_input_references = fields.iter().any(|f| f.holds_input_references());
+			Type::Composite { name: Cow::Borrowed(name), fields, holds_input_references }
+		},
+		other => return Err(tokens.unexpected("type", other)),
+	})
+}
+
+struct Tokens<'a, I : Iterator<Item = (usize, &'a str)>> {
+	tokens    : std::iter::Peekable<I>,
+	last_line : usize,
+}
+
+impl<'a, I : Iterator<Item = (usize, &'a str)>> Tokens<'a, I> {
+	fn peek(&mut self) -> Option<&'a str> { self.tokens.peek().map(|(_, t)| *t) }
+
+	fn next(&mut self, expected : &'static str) -> Result<&'a str> {
+		match self.tokens.next() {
+			Some((line, token)) => { self.last_line = line; Ok(token) },
+			None => Err(Error::InvalidSchema { line: self.last_line, expected, actual: "end of file".to_string() }),
+		}
+	}
+
+	fn number(&mut self, expected : &'static str) -> Result<usize> {
+		let token = self.next(expected)?;
+		token.parse().map_err(|_| self.unexpected(expected, token))
+	}
+
+	fn expect(&mut self, expected : &'static str) -> Result<()> {
+		let token = self.next(expected)?;
+		if token != expected { return Err(self.unexpected(expected, token)) }
+		Ok(())
+	}
+
+	/// Consumes the next token if it is `token`.
+	fn eat(&mut self, token : &str) -> bool {
+		if self.peek() != Some(token) { return false }
+		self.tokens.next();
+		true
+	}
+
+	fn unexpected(&self, expected : &'static str, actual : &str) -> Error {
+		Error::InvalidSchema { line: self.last_line, expected, actual: actual.to_string() }
+	}
+}
@@ -1,16 +1,29 @@
-#[derive(Debug)]
+use std::borrow::Cow;
+
+#[derive(Debug, PartialEq, Eq)]
 pub struct Chunk<'a> {
-	pub magic    : &'a str, //can be 4 or 3 bytes long
+	pub magic    : Cow<'a, str>, //can be 4 or 3 bytes long
 	pub holds_input_references : bool,
 	pub versions : Vec<SpecificChunkVersion<'a>>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct SpecificChunkVersion<'a> {
 	pub version : u32,
 	pub root : Type<'a>,
 }
 
+impl<'a> Chunk<'a> {
+	/// Detaches the chunk from the data it was read from, e.g. the game executable.
+	pub fn into_owned(self) -> Chunk<'static> {
+		Chunk {
+			magic   : Cow::Owned(self.magic.into_owned()),
+			holds_input_references: self.holds_input_references,
+			versions: self.versions.into_iter().map(|v| SpecificChunkVersion { version: v.version, root: v.root.into_owned() }).collect(),
+		}
+	}
+}
+
 impl<'a> std::ops::Deref for SpecificChunkVersion<'a> {
 	type Target = Type<'a>;
 	fn deref(&self) -> &Self::Target { &self.root }
@@ -32,7 +45,7 @@ pub enum Type<'a> {
 	Reference   { kind : ReferenceKind, inner : Box<Type<'a>> },
 	Array       { kind : ArrayKind, inner : Box<Type<'a>> },
 	Variant     { variants : Vec<Type<'a>>, holds_input_references : bool },
-	Composite   { name : &'a str, fields : Vec<Field<'a>>, holds_input_references : bool }
+	Composite   { name : Cow<'a, str>, fields : Vec<Field<'a>>, holds_input_references : bool }
 }
 
 #[derive(Debug, Hash, PartialEq, Eq)]
@@ -54,12 +67,41 @@ impl<'a> Type<'a> {
 		Self::Array { kind: ArrayKind::Inline { size }, inner: Box::new(inner) }
 	}
 
+	pub fn into_owned(self) -> Type<'static> {
+		match self {
+			Type::U8       => Type::U8,
+			Type::U16      => Type::U16,
+			Type::U32      => Type::U32,
+			Type::U64      => Type::U64,
+			Type::F32      => Type::F32,
+			Type::F64      => Type::F64,
+			Type::FileName => Type::FileName,
+			Type::FileRef  => Type::FileRef,
+			Type::Token    => Type::Token,
+			Type::UUID     => Type::UUID,
+			Type::CString { wide } => Type::CString { wide },
+			Type::Reference { kind, inner } => Type::Reference { kind, inner: Box::new(inner.into_owned()) },
+			Type::Array { kind, inner } => Type::Array { kind, inner: Box::new(inner.into_owned()) },
+			Type::Variant { variants, holds_input_references } => Type::Variant {
+				variants: variants.into_iter().map(Type::into_owned).collect(),
+				holds_input_references,
+			},
+			Type::Composite { name, fields, holds_input_references } => Type::Composite {
+				name  : Cow::Owned(name.into_owned()),
+				fields: fields.into_iter().map(|f| Field { name: Cow::Owned(f.name.into_owned()), _type: f._type.into_owned() }).collect(),
+				holds_input_references,
+			},
+		}
+	}
+
 	pub fn holds_input_references(&self) -> bool {
 		match self {
 			Type::CString {..} => true,
 			Type::Reference { inner, .. } => inner.holds_input_references(),
 			//NOTE(Rennorb): Byte arrays should not be copied over, their deserialize should just hold a pointer to the original data. 
-			Type::Array { inner, .. }  => matches!(inner.as_ref(), Type::U8) || inner.holds_input_references(),
+			// Only plain dynamic arrays are borrowed, the other kinds are read into `Vec<u8>` or `[u8; N]`.
+			Type::Array { kind: ArrayKind::Dynamic { .. }, inner } if matches!(inner.as_ref(), Type::U8) => true,
+			Type::Array { inner, .. }  => inner.holds_input_references(),
 			Type::Variant { holds_input_references, .. } |
 			Type::Composite { holds_input_references, .. } => *holds_input_references,
 			_ => false,
@@ -89,7 +131,7 @@ impl<'a> Type<'a> {
 
 #[derive(Debug, Hash, PartialEq, Eq)]
 pub struct Field<'a> {
-	pub name : &'a str,
+	pub name : Cow<'a, str>,
 	pub _type : Type<'a>,
 }
 
@@ -0,0 +1,147 @@
+use gw2_pf_typegen as dut;
+use dut::{decode::Value, structure::{ArrayKind, Chunk, Field, ReferenceKind, SpecificChunkVersion, Type}};
+use gw2_pf_rs::{parse::Input, serialize::{Output, Serialize}};
+
+fn composite<'a>(name : &'a str, fields : Vec<(&'a str, Type<'a>)>) -> Type<'a> {
+	Type::Composite { name: name.into(), fields: fields.into_iter().map(|(name, _type)| Field { name: name.into(), _type }).collect(), holds_input_references: false }
+}
+
+fn array<'a>(kind : ArrayKind, inner : Type<'a>) -> Type<'a> {
+	Type::Array { kind, inner: Box::new(inner) }
+}
+
+fn reference<'a>(kind : ReferenceKind, inner : Type<'a>) -> Type<'a> {
+	Type::Reference { kind, inner: Box::new(inner) }
+}
+
+fn single_version_chunk<'a>(magic : &'a str, version : u32, root : Type<'a>) -> Chunk<'a> {
+	Chunk { magic: magic.into(), holds_input_references: false, versions: vec![SpecificChunkVersion { version, root }] }
+}
+
+#[test]
+fn decode_txtv() {
+	let schema = [single_version_chunk("txtv", 0, composite("TextPackVoices", vec![
+		("mappings", array(ArrayKind::Dynamic { size: 0 }, composite("TextPackVoice", vec![
+			("textId", Type::U32),
+			("voiceId", Type::U32),
+		]))),
+	]))];
+
+	let data = std::fs::read("../gw2-pf/tests/res/198300.txtv").unwrap();
+	let raw = gw2_pf_rs::pf::RawPackFile::from_bytes(&data).map_err(|e| e.to_string()).unwrap().chunks.next().unwrap().map_err(|e| e.to_string()).unwrap();
+	let value = dut::decode::decode_raw_chunk(&schema, &raw).map_err(|e| e.to_string()).unwrap();
+
+	let typed = gw2_pf_rs::pf::PackFileReader::<gw2_pf_rs::formats::txtv>::from_bytes(&data).map_err(|e| e.to_string()).unwrap().next().unwrap().map_err(|e| e.to_string()).unwrap();
+	let gw2_pf_rs::formats::txtv::txtv(chunk) = &typed;
+	let Value::List(mappings) = value.get("mappings").unwrap() else { panic!("{value:?}") };
+	assert_eq!(mappings.len(), chunk.mappings.len());
+	for (dynamic, typed) in mappings.iter().zip(&chunk.mappings) {
+		assert_eq!(dynamic.get("textId").unwrap().as_u64(), Some(typed.text_id as u64));
+		assert_eq!(dynamic.get("voiceId").unwrap().as_u64(), Some(typed.voice_id as u64));
+	}
+}
+
+#[test]
+fn decode_abix() {
+	let schema = [single_version_chunk("BIDX", 0, composite("BankIndexData", vec![
+		("bankLanguage", array(ArrayKind::Dynamic { size: 0 }, composite("BankLanguageData", vec![
+			("bankFileName", array(ArrayKind::Dynamic { size: 0 }, composite("BankFileNameData", vec![
+				("fileName", Type::FileName),
+			]))),
+		]))),
+	]))];
+
+	let data = std::fs::read("../gw2-pf/tests/res/184691").unwrap();
+	let raw = gw2_pf_rs::pf::RawPackFile::from_bytes(&data).map_err(|e| e.to_string()).unwrap().chunks.next().unwrap().map_err(|e| e.to_string()).unwrap();
+	let value = dut::decode::decode_raw_chunk(&schema, &raw).map_err(|e| e.to_string()).unwrap();
+
+	let typed = gw2_pf_rs::pf::PackFileReader::<gw2_pf_rs::formats::ABIX>::from_bytes(&data).map_err(|e| e.to_string()).unwrap().next().unwrap().map_err(|e| e.to_string()).unwrap();
+	for (i, language) in typed.bank_language.iter().enumerate() {
+		for (j, file) in language.bank_file_name.iter().enumerate() {
+			let file = file.map_err(|e| e.to_string()).unwrap();
+			let dynamic = value.get("bankLanguage").unwrap().index(i).unwrap().get("bankFileName").unwrap().index(j).unwrap().get("fileName").unwrap();
+			match (dynamic, &file.file_name) {
+				(Value::FileName(dynamic), Some(typed)) => assert_eq!(dynamic.to_id(), typed.to_id()),
+				(Value::Null, None) => {},
+				other => panic!("{other:?}"),
+			}
+		}
+	}
+}
+
+#[test]
+fn decode_all_kinds() {
+	let _type = composite("Everything", vec![
+		("word", Type::U16),
+		("name", Type::CString { wide: false }),
+		("wideName", Type::CString { wide: true }),
+		("none", reference(ReferenceKind::Optional, Type::U32)),
+		("some", reference(ReferenceKind::Optional, Type::U32)),
+		("inline", reference(ReferenceKind::Inline, composite("Inner", vec![("x", Type::F32)]))),
+		("small", array(ArrayKind::DynamicSmall { size: 0 }, Type::U32)),
+		("pointers", array(ArrayKind::Pointers { size: 0 }, Type::U16)),
+		("fixed", array(ArrayKind::Fixed { size: 3 }, Type::U8)),
+		("position", Type::inline_array(Type::F32, 2)),
+		("variant", Type::Variant { variants: vec![Type::U32, Type::CString { wide: false }], holds_input_references: true }),
+		("fileRef", Type::FileRef),
+		("token", Type::Token),
+		("uuid", Type::UUID),
+	]);
+
+	for is_64_bit in [false, true] {
+		let mut output = Output::new(is_64_bit);
+		0x1234u16.serialize(&mut output).unwrap();
+		output.write_pointer(|o| { o.write_bytes(b"hello\0"); Ok(()) }).unwrap();
+		output.write_pointer(|o| { for c in "hi\0".encode_utf16() { c.serialize(o)? } Ok(()) }).unwrap();
+		output.write_null_pointer();
+		output.write_pointer(|o| 7u32.serialize(o)).unwrap();
+		1.5f32.serialize(&mut output).unwrap();
+		2u16.serialize(&mut output).unwrap();
+		output.write_pointer(|o| { 10u32.serialize(o)?; 11u32.serialize(o) }).unwrap();
+		2u32.serialize(&mut output).unwrap();
+		output.write_pointer(|o| { o.write_pointer(|o| 5u16.serialize(o))?; o.write_null_pointer(); Ok(()) }).unwrap();
+		output.write_bytes(&[1, 2, 3]);
+		(-1.0f32).serialize(&mut output).unwrap();
+		2.0f32.serialize(&mut output).unwrap();
+		1u32.serialize(&mut output).unwrap();
+		output.write_pointer(|o| o.write_pointer(|o| { o.write_bytes(b"v\0"); Ok(()) })).unwrap();
+		for part in [1u16, 2, 3] { part.serialize(&mut output).unwrap() }
+		0xdead_beefu64.serialize(&mut output).unwrap();
+		output.write_bytes(&[0xab; 16]);
+		let data = output.finish().unwrap();
+
+		let input = &mut Input::new(&data, is_64_bit);
+		let value = dut::decode::decode(&_type, input).map_err(|e| e.to_string()).unwrap();
+		let debug = format!("{value:?}");
+
+		assert_eq!(value.get("word").unwrap().as_u64(), Some(0x1234), "{debug}");
+		assert!(matches!(value.get("name"), Some(Value::String(s)) if s == "hello"), "{debug}");
+		assert!(matches!(value.get("wideName"), Some(Value::String(s)) if s == "hi"), "{debug}");
+		assert!(matches!(value.get("none"), Some(Value::Null)), "{debug}");
+		assert!(matches!(value.get("some"), Some(Value::U32(7))), "{debug}");
+		assert_eq!(value.get("inline").unwrap().get("x").unwrap().as_f64(), Some(1.5), "{debug}");
+		assert!(matches!(value.get("small"), Some(Value::List(l)) if matches!(l[..], [Value::U32(10), Value::U32(11)])), "{debug}");
+		assert!(matches!(value.get("pointers"), Some(Value::List(l)) if matches!(l[..], [Value::U16(5), Value::Null])), "{debug}");
+		assert!(matches!(value.get("fixed"), Some(Value::Bytes([1, 2, 3]))), "{debug}");
+		assert_eq!(value.get("position").unwrap().index(1).unwrap().as_f64(), Some(2.0), "{debug}");
+		assert!(matches!(value.get("variant"), Some(Value::Variant { index: 1, value }) if matches!(value.as_ref(), Value::String(s) if s == "v")), "{debug}");
+		assert!(matches!(value.get("fileRef"), Some(Value::FileRef(gw2_pf_rs::FileRef([1, 2, 3])))), "{debug}");
+		assert!(matches!(value.get("token"), Some(Value::Token(gw2_pf_rs::Token(0xdead_beef)))), "{debug}");
+		assert!(matches!(value.get("uuid"), Some(Value::UUID(gw2_pf_rs::UUID([0xab, ..])))), "{debug}");
+	}
+}
+
+#[test]
+fn reject_invalid_data() {
+	let _type = Type::Variant { variants: vec![Type::U32], holds_input_references: false };
+	let data = [5, 0, 0, 0, 8, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0];
+	assert!(matches!(dut::decode::decode(&_type, &mut Input::new(&data, false)), Err(gw2_pf_rs::parse::Error::UnknownVariant { actual: 5, .. })));
+
+	// the claimed length is far larger than the data, this must fail without allocating for all elements
+	let _type = array(ArrayKind::Dynamic { size: 0 }, Type::U32);
+	let data = [0xff, 0xff, 0xff, 0xff, 4, 0, 0, 0, 1, 0, 0, 0];
+	assert!(matches!(dut::decode::decode(&_type, &mut Input::new(&data, false)), Err(gw2_pf_rs::parse::Error::DataTooShort { .. })));
+
+	let chunk = single_version_chunk("txtv", 0, Type::U32);
+	assert!(matches!(dut::decode::decode_chunk(&chunk, 3, &mut Input::new(&data, false)), Err(gw2_pf_rs::parse::Error::UnknownVersion { actual: 3, .. })));
+}
@@ -0,0 +1,106 @@
+use gw2_pf_typegen as dut;
+use dut::{diff::{Change, TypeChange}, structure::{ArrayKind, Type}};
+
+const OLD : &str = "gw2-pf-schema 1
+chunk GONE {
+	version 0 u32
+}
+chunk ABIX {
+	version 0 struct BankIndexData {
+		languages array dynamic 0 struct BankLanguageData {
+			files array dynamic 0 struct BankFileNameData {
+				fileName filename
+				flags u16
+				removed u8
+			}
+		}
+	}
+	version 1 u32
+}
+chunk VARI {
+	version 0 variant {
+		u32
+		cstring
+	}
+}
+";
+
+const NEW : &str = "gw2-pf-schema 1
+chunk ABIX {
+	version 0 struct BankIndexData {
+		languages array dynamic 0 struct BankLanguageData {
+			files array dynamic 0 struct BankFileNameData {
+				fileName filename
+				flags u16
+				removed u8
+			}
+		}
+	}
+	version 2 struct BankIndexDataV2 {
+		languages array small 0 struct BankLanguageData {
+			files array dynamic 0 struct BankFileNameData {
+				fileName filename
+				flags u32
+				added token
+			}
+		}
+	}
+}
+chunk VARI {
+	version 0 variant {
+		u64
+		cstring
+		wcstring
+	}
+}
+chunk NEW {
+	version 0 u8
+}
+";
+
+#[test]
+fn diff_builds() {
+	let old = dut::schema::parse(OLD).map_err(|e| format!("{e:?}")).unwrap();
+	let new = dut::schema::parse(NEW).map_err(|e| format!("{e:?}")).unwrap();
+	let changes = dut::diff::diff(&old, &new);
+
+	let type_change = |old_version, new_version, path : &str, change| Change::TypeChanged { magic: "ABIX", old_version, new_version, path: path.to_string(), change };
+	assert_eq!(changes, vec![
+		Change::ChunkRemoved { magic: "GONE" },
+		Change::VersionRemoved { magic: "ABIX", version: 1 },
+		Change::VersionAdded { magic: "ABIX", version: 2 },
+		type_change(1, 2, "", TypeChange::Retyped { old: &Type::U32, new: &new[0].versions[1].root }),
+		Change::TypeChanged { magic: "VARI", old_version: 0, new_version: 0, path: "<0>".to_string(), change: TypeChange::Retyped { old: &Type::U32, new: &Type::U64 } },
+		Change::TypeChanged { magic: "VARI", old_version: 0, new_version: 0, path: "".to_string(), change: TypeChange::VariantAdded { index: 2, _type: &Type::CString { wide: true } } },
+		Change::ChunkAdded { magic: "NEW" },
+	]);
+
+	// compare against the structurally similar version 0 instead
+	let mut old = old;
+	old[1].versions.pop();
+	let changes = dut::diff::diff(&old[1..2], &new[0..1]);
+	assert_eq!(changes, vec![
+		Change::VersionAdded { magic: "ABIX", version: 2 },
+		type_change(0, 2, "", TypeChange::Renamed { old: "BankIndexData", new: "BankIndexDataV2" }),
+		type_change(0, 2, "languages", TypeChange::ArrayKindChanged { old: &ArrayKind::Dynamic { size: 0 }, new: &ArrayKind::DynamicSmall { size: 0 } }),
+		type_change(0, 2, "languages[].files[]", TypeChange::FieldRemoved { name: "removed", _type: &Type::U8 }),
+		type_change(0, 2, "languages[].files[].flags", TypeChange::Retyped { old: &Type::U16, new: &Type::U32 }),
+		type_change(0, 2, "languages[].files[]", TypeChange::FieldAdded { name: "added", _type: &Type::Token }),
+	]);
+
+	assert_eq!(dut::diff::report(&changes), "\
++ ABIX v2
+~ ABIX v0 -> v2 <root>: struct renamed: BankIndexData -> BankIndexDataV2
+~ ABIX v0 -> v2 languages: array kind changed: Dynamic { size: 0 } -> DynamicSmall { size: 0 }
+~ ABIX v0 -> v2 languages[].files[]: field removed: removed : u8
+~ ABIX v0 -> v2 languages[].files[].flags: type changed: u16 -> u32
+~ ABIX v0 -> v2 languages[].files[]: field added: added : token
+");
+}
+
+#[test]
+fn identical_builds_have_no_changes() {
+	let old = dut::schema::parse(OLD).map_err(|e| format!("{e:?}")).unwrap();
+	let new = dut::schema::parse(OLD).map_err(|e| format!("{e:?}")).unwrap();
+	assert!(dut::diff::diff(&old, &new).is_empty());
+}
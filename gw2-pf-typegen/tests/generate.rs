@@ -0,0 +1,68 @@
+use std::process::Command;
+use gw2_pf_typegen as dut;
+
+#[test]
+fn chunk_layout() {
+	let chunks = dut::schema::load("tests/res/modeled_formats.schema").map_err(|e| format!("{e:?}")).unwrap();
+	let txtv = chunks.iter().find(|c| c.magic == "txtv").unwrap();
+
+	let files = dut::generate::chunk_files(txtv, dut::generate::Language::Rust).map_err(|e| format!("{e:?}")).unwrap();
+	let paths = files.iter().map(|f| f.path.to_str().unwrap().replace('\\', "/")).collect::<Vec<_>>();
+	assert_eq!(paths, ["txtv/txtv/txtv.rs", "txtv/txtv/v0.rs"]);
+	assert!(files[0].contents.starts_with("pub mod v0;\n"));
+	assert!(files[0].contents.contains("#[v(0)] V0(v0::TextPackVoices),"));
+	assert!(files[1].contents.contains("pub struct TextPackVoice {"));
+
+	let files = dut::generate::chunk_files(txtv, dut::generate::Language::Odin).map_err(|e| format!("{e:?}")).unwrap();
+	let paths = files.iter().map(|f| f.path.to_str().unwrap().replace('\\', "/")).collect::<Vec<_>>();
+	assert_eq!(paths, ["txtv/txtv/txtv.odin", "txtv/txtv/v0/txtv-v0.odin"]);
+}
+
+#[test]
+fn reject_unsafe_magic() {
+	let mut chunks = dut::schema::load("tests/res/modeled_formats.schema").map_err(|e| format!("{e:?}")).unwrap();
+	for magic in ["../../x", "tx/v", "tx.v", "txtvx", "tx", ""] {
+		chunks[0].magic = magic.into();
+		let result = dut::generate::chunk_files(&chunks[0], dut::generate::Language::Rust);
+		assert!(matches!(result, Err(dut::Error::InvalidChunkMagic { magic: ref m }) if m == magic), "{magic}");
+	}
+	chunks[0].magic = "AMP".into();
+	assert!(dut::generate::chunk_files(&chunks[0], dut::generate::Language::Rust).is_ok());
+}
+
+#[test]
+fn generate_from_schema() {
+	let out = std::env::temp_dir().join(format!("gw2-pf-typegen-generate-{}", std::process::id()));
+	let run = |args : &[&str]| Command::new(env!("CARGO_BIN_EXE_gw2-pf-typegen")).args(args).arg("tests/res/modeled_formats.schema").arg(&out).output().unwrap();
+
+	let output = run(&["--magic", "BIDX"]);
+	assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+	assert!(out.join("bidx/bidx/bidx.rs").exists());
+	assert!(out.join("bidx/bidx/v0.rs").exists());
+	assert!(!out.join("txtv").exists());
+
+	// existing files are kept unless --overwrite is given
+	std::fs::write(out.join("bidx/bidx/v0.rs"), "").unwrap();
+	assert!(run(&["--magic", "BIDX"]).status.success());
+	assert_eq!(std::fs::read_to_string(out.join("bidx/bidx/v0.rs")).unwrap(), "");
+	assert!(run(&["--magic", "BIDX", "--overwrite"]).status.success());
+	assert!(std::fs::read_to_string(out.join("bidx/bidx/v0.rs")).unwrap().contains("use crate::FileName;"));
+
+	assert!(run(&["--lang", "odin"]).status.success());
+	assert!(out.join("txtv/txtv/v0/txtv-v0.odin").exists());
+
+	assert!(!run(&["--lang", "cobol"]).status.success());
+	_ = std::fs::remove_dir_all(&out);
+}
+
+/// The derive tests of gw2-pf compile and parse a copy of this output, it has to stay in sync.
+#[test]
+fn field_kinds_match_derive_tests() {
+	let chunks = dut::schema::load("tests/res/field_kinds.schema").map_err(|e| format!("{e:?}")).unwrap();
+	let files = dut::generate::chunk_files(&chunks[0], dut::generate::Language::Rust).map_err(|e| format!("{e:?}")).unwrap();
+	for file in files {
+		let name = file.path.file_name().unwrap();
+		let expected = std::fs::read_to_string(std::path::Path::new("../gw2-pf/tests/generated/fknd").join(name)).unwrap();
+		assert_eq!(file.contents, expected, "{name:?}");
+	}
+}
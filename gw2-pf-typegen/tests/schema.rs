@@ -0,0 +1,80 @@
+use gw2_pf_typegen as dut;
+
+const ALL_KINDS : &str = "gw2-pf-schema 1
+chunk ABC {
+	version 0 u32
+	version 3 struct Root {
+		name cstring
+		wideName wcstring
+		file filename
+		ref fileref
+		token token
+		id uuid
+		optional optional struct Inner { x f32 y f64 }
+		inline inline u8
+		common common u16
+		words array small 0 u16
+		pointers array pointers 2 u64
+		position array inline 3 f32
+		fixed array fixed 4 u8
+		dynamic array dynamic 0 struct Inner { x f32 y f64 }
+		variant variant {
+			u32
+			struct Other { value cstring }
+		}
+	}
+}
+chunk ABCD {
+	version 1 u8
+}
+";
+
+#[test]
+fn round_trip() {
+	let chunks = dut::schema::parse(ALL_KINDS).map_err(|e| format!("{e:?}")).unwrap();
+	assert_eq!(chunks.len(), 2);
+	assert_eq!(chunks[0].magic, "ABC");
+	assert_eq!(chunks[0].versions.len(), 2);
+	assert!(chunks[0].holds_input_references);
+	assert!(!chunks[1].holds_input_references);
+
+	let text = dut::schema::serialize(&chunks).map_err(|e| format!("{e:?}")).unwrap();
+	let reparsed = dut::schema::parse(&text).map_err(|e| format!("{e:?}")).unwrap();
+	assert_eq!(chunks, reparsed);
+	assert_eq!(text, dut::schema::serialize(&reparsed).map_err(|e| format!("{e:?}")).unwrap());
+
+	let path = std::env::temp_dir().join(format!("gw2-pf-typegen-schema-{}.schema", std::process::id()));
+	dut::schema::save(&path, &chunks).map_err(|e| format!("{e:?}")).unwrap();
+	let loaded = dut::schema::load(&path).map_err(|e| format!("{e:?}")).unwrap();
+	_ = std::fs::remove_file(&path);
+	assert_eq!(chunks, loaded);
+}
+
+#[test]
+fn decode_with_snapshot() {
+	let chunks = dut::schema::load("tests/res/modeled_formats.schema").map_err(|e| format!("{e:?}")).unwrap();
+
+	let data = std::fs::read("../gw2-pf/tests/res/198300.txtv").unwrap();
+	let raw = gw2_pf_rs::pf::RawPackFile::from_bytes(&data).map_err(|e| e.to_string()).unwrap().chunks.next().unwrap().map_err(|e| e.to_string()).unwrap();
+	let value = dut::decode::decode_raw_chunk(&chunks, &raw).map_err(|e| e.to_string()).unwrap();
+	assert!(value.get("mappings").unwrap().index(0).unwrap().get("voiceId").is_some());
+}
+
+#[test]
+fn reject_invalid_schema() {
+	use dut::Error;
+
+	assert!(matches!(dut::schema::parse("gw2-pf-schema 99\n"), Err(Error::UnsupportedSchemaVersion { actual: 99 })));
+	assert!(matches!(dut::schema::parse("something else"), Err(Error::InvalidSchema { line: 1, expected: "gw2-pf-schema", .. })));
+	assert!(matches!(dut::schema::parse("gw2-pf-schema 1\nchunk A {\n\tversion 0 u33\n}\n"), Err(Error::InvalidSchema { line: 3, expected: "type", actual }) if actual == "u33"));
+	assert!(matches!(dut::schema::parse("gw2-pf-schema 1\nchunk A {\n\tversion 0 struct S {\n"), Err(Error::InvalidSchema { line: 3, actual, .. }) if actual == "end of file"));
+
+	let mut chunks = dut::schema::parse("gw2-pf-schema 1\nchunk A {\n\tversion 0 struct S { a u8 }\n}\n").map_err(|e| format!("{e:?}")).unwrap();
+	let dut::structure::Type::Composite { fields, .. } = &mut chunks[0].versions[0].root else { unreachable!() };
+	fields[0].name = "a b".into();
+	assert!(matches!(dut::schema::serialize(&chunks), Err(Error::InvalidSchemaName { name }) if name == "a b"));
+
+	assert!(matches!(dut::schema::parse("gw2-pf-schema 1\nchunk ../../x {\n\tversion 0 u8\n}\n"), Err(Error::InvalidSchemaName { name }) if name == "../../x"));
+	assert!(matches!(dut::schema::parse("gw2-pf-schema 1\nchunk A {\n\tversion 0 struct S/T { a u8 }\n}\n"), Err(Error::InvalidSchemaName { name }) if name == "S/T"));
+	assert!(matches!(dut::schema::parse("gw2-pf-schema 1\nchunk A {\n\tversion 0 struct S { a; u8 }\n}\n"), Err(Error::InvalidSchemaName { name }) if name == "a;"));
+}
@@ -0,0 +1,359 @@
+//! Decompression of compressed Gw2.dat entries.
+//!
+//! The stream is read as little endian dwords whose bits are consumed most significant bit first.
+//! Every 0x4000th dword is a crc and is skipped.
+//! After a dword that is ignored and one holding the decompressed size follow blocks of
+//! two huffman trees (symbols and copy offsets) and a list of codes. Symbols below 0x100 are literal bytes,
+//! the others encode the length of a back reference whose offset is then read with the second tree.
+//! The trees themselves are stored as code length runs encoded with a static dictionary tree.
+
+use crate::parse::{Error, Result};
+
+const MAX_CODE_BITS    : usize = 32;
+const MAX_SYMBOL_VALUE : usize = 285;
+/// Every 0x4000th dword of the input is a crc.
+const CRC_INTERVAL     : usize = 0x4000;
+
+/// Symbols of the static dictionary tree by code length, in insertion order.
+/// All remaining byte values are 16 bits long and inserted in descending order.
+const DICTIONARY_CODES : &[(u8, &[u16])] = &[
+	( 3, &[0x0A, 0x09, 0x08]),
+	( 4, &[0x0C, 0x0B, 0x07, 0x00]),
+	( 5, &[0xE0, 0x2A, 0x29, 0x06]),
+	( 6, &[0x4A, 0x40, 0x2C, 0x2B, 0x28, 0x20, 0x05, 0x04]),
+	( 7, &[0x49, 0x48, 0x27, 0x26, 0x25, 0x0D, 0x03]),
+	( 8, &[0x6A, 0x69, 0x4C, 0x4B, 0x47, 0x24]),
+	( 9, &[0xE8, 0xA0, 0x89, 0x88, 0x68, 0x67, 0x63, 0x60, 0x46, 0x23]),
+	(10, &[0xE9, 0xC9, 0xC0, 0xA9, 0xA8, 0x8A, 0x87, 0x80, 0x66, 0x65, 0x45, 0x44, 0x43, 0x2D, 0x02, 0x01]),
+	(11, &[0xE5, 0xC8, 0xAA, 0xA5, 0xA4, 0x8B, 0x85, 0x84, 0x6C, 0x6B, 0x64, 0x4D, 0x0E]),
+	(12, &[0xE7, 0xCA, 0xC7, 0xA7, 0xA6, 0x86, 0x83]),
+	(13, &[0xE6, 0xE4, 0xC4, 0x8C, 0x2E, 0x22]),
+	(14, &[0xEC, 0xC6, 0x6D, 0x4E]),
+	(15, &[0xEA, 0xCC, 0xAC, 0xAB, 0x8D, 0x11, 0x10, 0x0F]),
+];
+
+/// Returns the decompressed size stored in the stream header.
+pub fn inflated_size(input : &[u8]) -> Result<usize> {
+	let reader = &mut BitReader::new(input);
+	read_header(reader)
+}
+
+pub fn inflate(input : &[u8]) -> Result<Vec<u8>> {
+	let reader = &mut BitReader::new(input);
+	let size = read_header(reader)?;
+	// the size comes from the stream, don't trust it for the allocation
+	let mut output = Vec::with_capacity(std::cmp::min(size, input.len().saturating_mul(8)));
+	inflate_data(reader, &mut output, size)?;
+	Ok(output)
+}
+
+/// Decompresses at most `output.len()` bytes into `output` and returns the number of bytes written.
+pub fn inflate_into(input : &[u8], output : &mut [u8]) -> Result<usize> {
+	let reader = &mut BitReader::new(input);
+	let size = std::cmp::min(read_header(reader)?, output.len());
+	let output = &mut SliceOutput { slice: output, position: 0 };
+	inflate_data(reader, output, size)?;
+	Ok(output.position)
+}
+
+fn read_header(reader : &mut BitReader) -> Result<usize> {
+	reader.need_bits(32)?;
+	reader.drop_bits(32);
+	reader.need_bits(32)?;
+	let size = reader.read_bits(32) as usize;
+	reader.drop_bits(32);
+	Ok(size)
+}
+
+fn inflate_data(reader : &mut BitReader, output : &mut impl OutputBuffer, size : usize) -> Result<()> {
+	if size == 0 { return Ok(()) }
+
+	reader.need_bits(8)?;
+	reader.drop_bits(4);
+	let copy_size_add = reader.read_bits(4) + 1;
+	reader.drop_bits(4);
+
+	let dictionary = dictionary_tree();
+	while output.position() < size {
+		let symbol_tree = HuffmanTree::parse(reader, dictionary)?;
+		let copy_tree = HuffmanTree::parse(reader, dictionary)?;
+
+		reader.need_bits(4)?;
+		let max_codes = (reader.read_bits(4) + 1) << 12;
+		reader.drop_bits(4);
+
+		let mut codes_read = 0;
+		while codes_read < max_codes && output.position() < size {
+			codes_read += 1;
+
+			let code = symbol_tree.read_code(reader)?;
+			if code < 0x100 {
+				output.push(code as u8);
+				continue
+			}
+
+			let code = code - 0x100;
+			let (quot, rem) = (code / 4, code % 4);
+			let mut copy_size = match quot {
+				0 => code as u32,
+				1..=6 => (1 << (quot - 1)) * (4 + rem as u32),
+				_ if code == 28 => 0xff,
+				_ => return Err(Error::InvalidCopySize { code }),
+			};
+			if quot > 1 && code != 28 {
+				let extra_bits = quot as u8 - 1;
+				reader.need_bits(extra_bits)?;
+				copy_size |= reader.read_bits(extra_bits);
+				reader.drop_bits(extra_bits);
+			}
+			copy_size += copy_size_add;
+
+			let code = copy_tree.read_code(reader)?;
+			let (quot, rem) = (code / 2, code % 2);
+			let mut copy_offset = match quot {
+				0 => code as u32,
+				1..=16 => (1 << (quot - 1)) * (2 + rem as u32),
+				_ => return Err(Error::InvalidCopyOffset { code }),
+			};
+			if quot > 1 {
+				let extra_bits = quot as u8 - 1;
+				reader.need_bits(extra_bits)?;
+				copy_offset |= reader.read_bits(extra_bits);
+				reader.drop_bits(extra_bits);
+			}
+			let copy_offset = copy_offset as usize + 1;
+
+			if copy_offset > output.position() {
+				return Err(Error::CopyOutOfBounds { offset: copy_offset, position: output.position() })
+			}
+
+			let mut copied = 0;
+			while copied < copy_size && output.position() < size {
+				output.copy_back(copy_offset);
+				copied += 1;
+			}
+		}
+	}
+
+	Ok(())
+}
+
+trait OutputBuffer {
+	fn position(&self) -> usize;
+	fn push(&mut self, byte : u8);
+	/// Pushes the byte `offset` bytes before the current position.
+	fn copy_back(&mut self, offset : usize);
+}
+
+impl OutputBuffer for Vec<u8> {
+	fn position(&self) -> usize { self.len() }
+	fn push(&mut self, byte : u8) { Vec::push(self, byte) }
+	fn copy_back(&mut self, offset : usize) { self.push(self[self.len() - offset]) }
+}
+
+struct SliceOutput<'a> {
+	slice    : &'a mut [u8],
+	position : usize,
+}
+
+impl OutputBuffer for SliceOutput<'_> {
+	fn position(&self) -> usize { self.position }
+	fn push(&mut self, byte : u8) {
+		self.slice[self.position] = byte;
+		self.position += 1;
+	}
+	fn copy_back(&mut self, offset : usize) { self.push(self.slice[self.position - offset]) }
+}
+
+struct BitReader<'a> {
+	input          : &'a [u8],
+	/// in dwords
+	position       : usize,
+	head           : u32,
+	buffer         : u32,
+	bits_available : u8,
+}
+
+impl<'a> BitReader<'a> {
+	fn new(input : &'a [u8]) -> Self {
+		Self { input, position: 0, head: 0, buffer: 0, bits_available: 0 }
+	}
+
+	fn pull_dword(&mut self) -> Result<()> {
+		if (self.position + 1).is_multiple_of(CRC_INTERVAL) { self.position += 1 }
+
+		let start = self.position * 4;
+		//NOTE(Rennorb): Reading a code always looks ahead 32 bits, so the end of the stream has to be padded.
+		// One dword is enough for that, anything beyond is an actual read past the end.
+		if start >= self.input.len() + 4 {
+			return Err(Error::DataTooShort { r#type: Some("compressed stream"), required: start + 4, actual: self.input.len() })
+		}
+		let mut bytes = [0; 4];
+		if start < self.input.len() {
+			let available = &self.input[start..];
+			let n = std::cmp::min(4, available.len());
+			bytes[..n].copy_from_slice(&available[..n]);
+		}
+		let value = u32::from_le_bytes(bytes);
+
+		if self.bits_available == 0 {
+			self.head = value;
+			self.buffer = 0;
+		}
+		else {
+			self.head |= value >> self.bits_available;
+			self.buffer = value << (32 - self.bits_available);
+		}
+		self.bits_available += 32;
+		self.position += 1;
+		Ok(())
+	}
+
+	fn need_bits(&mut self, bits : u8) -> Result<()> {
+		if self.bits_available < bits { self.pull_dword()?; }
+		Ok(())
+	}
+
+	fn read_bits(&self, bits : u8) -> u32 {
+		if bits == 0 { 0 } else { self.head >> (32 - bits) }
+	}
+
+	fn drop_bits(&mut self, bits : u8) {
+		debug_assert!(bits <= self.bits_available);
+		match bits {
+			0 => {},
+			32 => {
+				self.head = self.buffer;
+				self.buffer = 0;
+			},
+			_ => {
+				self.head = (self.head << bits) | (self.buffer >> (32 - bits));
+				self.buffer <<= bits;
+			},
+		}
+		self.bits_available -= bits;
+	}
+}
+
+struct HuffmanTree {
+	/// smallest code (left aligned) for each used code length, descending
+	code_comparison : [u32; MAX_CODE_BITS],
+	/// index into `symbols` of the symbol with the smallest code of that length
+	symbol_offsets  : [u16; MAX_CODE_BITS],
+	code_bits       : [u8; MAX_CODE_BITS],
+	symbols         : [u16; MAX_SYMBOL_VALUE],
+	n_lengths       : usize,
+}
+
+impl HuffmanTree {
+	fn parse(reader : &mut BitReader, dictionary : &HuffmanTree) -> Result<Self> {
+		reader.need_bits(16)?;
+		let n_symbols = reader.read_bits(16) as usize;
+		reader.drop_bits(16);
+		if n_symbols > MAX_SYMBOL_VALUE { return Err(Error::InvalidHuffmanTree) }
+
+		let mut builder = HuffmanTreeBuilder::new();
+		let mut remaining = n_symbols as isize - 1;
+		while remaining >= 0 {
+			let code = dictionary.read_code(reader)?;
+			let bits = (code & 0x1f) as u8;
+			let count = (code >> 5) as isize + 1;
+			if bits == 0 {
+				remaining -= count;
+			}
+			else {
+				for _ in 0..count {
+					if remaining < 0 { return Err(Error::InvalidHuffmanTree) }
+					builder.add(bits, remaining as u16);
+					remaining -= 1;
+				}
+			}
+		}
+
+		builder.build()
+	}
+
+	fn read_code(&self, reader : &mut BitReader) -> Result<u16> {
+		reader.need_bits(32)?;
+		let value = reader.read_bits(32);
+
+		let Some(index) = (0..self.n_lengths).find(|i| value >= self.code_comparison[*i]) else {
+			return Err(Error::InvalidHuffmanCode)
+		};
+		let bits = self.code_bits[index];
+		let distance = (value - self.code_comparison[index]) >> (32 - bits);
+		let symbol_index = (self.symbol_offsets[index] as u32).checked_sub(distance).ok_or(Error::InvalidHuffmanCode)?;
+		let symbol = self.symbols[symbol_index as usize];
+
+		reader.drop_bits(bits);
+		Ok(symbol)
+	}
+}
+
+/// Collects symbols per code length as singly linked lists, the last symbol added to a length gets the highest code.
+struct HuffmanTreeBuilder {
+	heads : [i16; MAX_CODE_BITS],
+	next  : [i16; MAX_SYMBOL_VALUE],
+}
+
+impl HuffmanTreeBuilder {
+	fn new() -> Self {
+		Self { heads: [-1; MAX_CODE_BITS], next: [-1; MAX_SYMBOL_VALUE] }
+	}
+
+	fn add(&mut self, bits : u8, symbol : u16) {
+		self.next[symbol as usize] = self.heads[bits as usize];
+		self.heads[bits as usize] = symbol as i16;
+	}
+
+	fn build(&self) -> Result<HuffmanTree> {
+		let mut tree = HuffmanTree {
+			code_comparison: [0; MAX_CODE_BITS],
+			symbol_offsets : [0; MAX_CODE_BITS],
+			code_bits      : [0; MAX_CODE_BITS],
+			symbols        : [0; MAX_SYMBOL_VALUE],
+			n_lengths      : 0,
+		};
+
+		let mut code : u32 = 0;
+		let mut n_symbols = 0;
+		for bits in 0..MAX_CODE_BITS {
+			let mut symbol = self.heads[bits];
+			if symbol != -1 {
+				while symbol != -1 {
+					if n_symbols == MAX_SYMBOL_VALUE { return Err(Error::InvalidHuffmanTree) }
+					tree.symbols[n_symbols] = symbol as u16;
+					n_symbols += 1;
+					symbol = self.next[symbol as usize];
+					code = code.wrapping_sub(1);
+				}
+
+				tree.code_comparison[tree.n_lengths] = code.wrapping_add(1) << (32 - bits);
+				tree.code_bits[tree.n_lengths] = bits as u8;
+				tree.symbol_offsets[tree.n_lengths] = n_symbols as u16 - 1;
+				tree.n_lengths += 1;
+			}
+			code = (code << 1).wrapping_add(1);
+		}
+
+		Ok(tree)
+	}
+}
+
+fn dictionary_tree() -> &'static HuffmanTree {
+	static TREE : std::sync::OnceLock<HuffmanTree> = std::sync::OnceLock::new();
+	TREE.get_or_init(|| {
+		let mut builder = HuffmanTreeBuilder::new();
+		let mut is_assigned = [false; 0x100];
+		for (bits, symbols) in DICTIONARY_CODES {
+			for symbol in *symbols {
+				builder.add(*bits, *symbol);
+				is_assigned[*symbol as usize] = true;
+			}
+		}
+		for symbol in (0..0x100).rev() {
+			if !is_assigned[symbol] { builder.add(16, symbol as u16); }
+		}
+		builder.build().unwrap()
+	})
+}
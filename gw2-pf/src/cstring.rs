@@ -0,0 +1,111 @@
+use std::{borrow::Cow, ffi::CStr};
+use crate::{parse::{BinarySize, Error, Input, Parse, Result}, serialize::{Output, Serialize}, wstr::WStr};
+
+/// Pointer to a null terminated 8 bit string, the pointer may be null.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CString<'inp>(Option<&'inp CStr>);
+
+impl<'inp> CString<'inp> {
+	pub fn is_null(&self) -> bool { self.0.is_none() }
+
+	pub fn as_c_str(&self) -> Option<&'inp CStr> { self.0 }
+
+	/// The bytes without the terminator, empty for null pointers.
+	pub fn as_bytes(&self) -> &'inp [u8] { self.0.map_or(&[], CStr::to_bytes) }
+
+	pub fn to_str(&self) -> std::result::Result<&'inp str, std::str::Utf8Error> { std::str::from_utf8(self.as_bytes()) }
+
+	pub fn to_string_lossy(&self) -> Cow<'inp, str> { String::from_utf8_lossy(self.as_bytes()) }
+}
+
+impl<'inp> Parse<'inp> for CString<'inp> {
+	const BINARY_SIZE : BinarySize = BinarySize::ptrs(1);
+	fn parse(input : &mut Input<'inp>) -> Result<Self> {
+		let Some(offset) = input.eat_offset()? else { return Ok(Self(None)) };
+		let target = input.clone_with_offset(offset)?;
+		let string = CStr::from_bytes_until_nul(target.remaining).map_err(|_| Error::CannotFindNullTerminator)?;
+		target.record_read(string.count_bytes() + 1);
+		Ok(Self(Some(string)))
+	}
+}
+
+impl Serialize for CString<'_> {
+	fn serialize(&self, output : &mut Output) -> Result<()> {
+		match self.0 {
+			Some(string) => output.write_pointer(|output| { output.write_bytes(string.to_bytes_with_nul()); Ok(()) }),
+			None => { output.write_null_pointer(); Ok(()) },
+		}
+	}
+}
+
+impl std::fmt::Display for CString<'_> {
+	fn fmt(&self, f : &mut std::fmt::Formatter<'_>) -> std::fmt::Result { f.write_str(&self.to_string_lossy()) }
+}
+
+impl std::fmt::Debug for CString<'_> {
+	fn fmt(&self, f : &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.0 {
+			Some(string) => string.fmt(f),
+			None => f.write_str("null"),
+		}
+	}
+}
+
+/// Null pointers are written as `None`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for CString<'_> {
+	fn serialize<S : serde::Serializer>(&self, serializer : S) -> std::result::Result<S::Ok, S::Error> {
+		match self.0 {
+			Some(_) => serializer.serialize_str(&self.to_string_lossy()),
+			None => serializer.serialize_none(),
+		}
+	}
+}
+
+/// Pointer to a null terminated utf16 string, the pointer may be null.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct WideCString<'inp>(Option<WStr<'inp>>);
+
+impl<'inp> WideCString<'inp> {
+	pub fn is_null(&self) -> bool { self.0.is_none() }
+
+	pub fn as_wstr(&self) -> Option<WStr<'inp>> { self.0 }
+
+	/// Empty for null pointers.
+	pub fn to_string_lossy(&self) -> String { self.0.map(|s| s.to_string_lossy()).unwrap_or_default() }
+}
+
+impl<'inp> Parse<'inp> for WideCString<'inp> {
+	const BINARY_SIZE : BinarySize = BinarySize::ptrs(1);
+	fn parse(input : &mut Input<'inp>) -> Result<Self> { Option::<WStr>::parse(input).map(Self) }
+}
+
+impl Serialize for WideCString<'_> {
+	fn serialize(&self, output : &mut Output) -> Result<()> { self.0.serialize(output) }
+}
+
+impl std::fmt::Display for WideCString<'_> {
+	fn fmt(&self, f : &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.0 {
+			Some(string) => string.fmt(f),
+			None => Ok(()),
+		}
+	}
+}
+
+impl std::fmt::Debug for WideCString<'_> {
+	fn fmt(&self, f : &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.0 {
+			Some(string) => string.fmt(f),
+			None => f.write_str("null"),
+		}
+	}
+}
+
+/// Null pointers are written as `None`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for WideCString<'_> {
+	fn serialize<S : serde::Serializer>(&self, serializer : S) -> std::result::Result<S::Ok, S::Error> {
+		serde::Serialize::serialize(&self.0, serializer)
+	}
+}
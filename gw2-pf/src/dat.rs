@@ -0,0 +1,186 @@
+//! Reader for the Gw2.dat archive.
+//!
+//! The archive starts with a [`DatHeader`] that points to the master file table (MFT).
+//! The MFT is a list of [`MftEntry`] records where record 0 is occupied by the [`MftHeader`] itself.
+//! Record [`FILE_ID_TABLE_INDEX`] holds a list of [`FileIdEntry`] pairs mapping file ids (as returned by [`crate::FileName::to_id`]) to MFT records.
+
+use std::{collections::HashMap, io::{Read, Seek, SeekFrom}};
+use crate::parse::{Input, Parse};
+
+/// `"\0AN\x1a"`, the lowest byte holds the archive version
+pub const DAT_MAGIC : u32 = crate::fcc(b"\0AN\x1a");
+pub const DAT_MAGIC_MASK : u32 = 0xffffff00;
+pub const MFT_MAGIC : u32 = crate::fcc(b"Mft\x1a");
+/// MFT record containing the file id table. Record 0 is the MFT header.
+pub const FILE_ID_TABLE_INDEX : usize = 2;
+
+#[derive(Debug, crate::Parse)]
+pub struct DatHeader {
+	/// version in the lowest byte, see [`DAT_MAGIC`]
+	pub magic       : u32,
+	pub header_size : u32,
+	   _reserved1   : u32,
+	pub chunk_size  : u32,
+	pub crc         : u32,
+	   _reserved2   : u32,
+	pub mft_offset  : u64,
+	pub mft_size    : u32,
+	pub flags       : u32,
+}
+
+impl DatHeader {
+	pub fn version(&self) -> u8 { self.magic as u8 }
+}
+
+#[derive(Debug, crate::Parse)]
+pub struct MftHeader {
+	pub magic       : u32,
+	   _reserved1   : u64,
+	pub num_entries : u32,
+	   _reserved2   : u32,
+	   _reserved3   : u32,
+}
+
+#[derive(Debug, Clone, crate::Parse)]
+pub struct MftEntry {
+	pub offset           : u64,
+	pub size             : u32,
+	pub compression_flag : u16,
+	pub entry_flag       : u16,
+	pub counter          : u32,
+	pub crc              : u32,
+}
+
+impl MftEntry {
+	pub fn is_compressed(&self) -> bool { self.compression_flag != 0 }
+}
+
+#[derive(Debug, crate::Parse)]
+pub struct FileIdEntry {
+	pub file_id   : u32,
+	pub mft_index : u32,
+}
+
+#[derive(Debug)]
+pub enum Error {
+	Io(std::io::Error),
+	Parse(crate::parse::Error),
+	FileNotFound { file_id : u32 },
+	EntryOutOfBounds { index : usize, num_entries : usize },
+}
+
+impl std::fmt::Display for Error {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Error::Io(e) => f.write_fmt(format_args!("IO error: {e}")),
+			Error::Parse(e) => e.fmt(f),
+			Error::FileNotFound { file_id } => f.write_fmt(format_args!("No file with id {file_id} in archive")),
+			Error::EntryOutOfBounds { index, num_entries } => f.write_fmt(format_args!("MFT entry {index} out of bounds, archive has {num_entries} entries")),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+	fn from(value : std::io::Error) -> Self { Self::Io(value) }
+}
+impl From<crate::parse::Error> for Error {
+	fn from(value : crate::parse::Error) -> Self { Self::Parse(value) }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+pub struct DatFile<R : Read + Seek> {
+	reader         : R,
+	pub header     : DatHeader,
+	pub mft_header : MftHeader,
+	/// Indexed by MFT record, record 0 (the header) is included as a zeroed entry to keep indices aligned.
+	entries        : Vec<MftEntry>,
+	file_ids       : HashMap<u32, usize>,
+}
+
+impl DatFile<std::fs::File> {
+	pub fn open_path(path : impl AsRef<std::path::Path>) -> Result<Self> {
+		Self::open(std::fs::File::open(path)?)
+	}
+}
+
+impl<R : Read + Seek> DatFile<R> {
+	pub fn open(mut reader : R) -> Result<Self> {
+		let header : DatHeader = read_struct(&mut reader, 0)?;
+		if header.magic & DAT_MAGIC_MASK != DAT_MAGIC {
+			return Err(crate::parse::Error::InvalidFileType { r#type: std::any::type_name::<DatHeader>(), expected: DAT_MAGIC, actual: header.magic }.into())
+		}
+
+		let mft_data = read_bytes(&mut reader, header.mft_offset, header.mft_size as usize)?;
+		let input = &mut Input::new(&mft_data, false);
+		let mft_header = MftHeader::parse(input)?;
+		if mft_header.magic != MFT_MAGIC {
+			return Err(crate::parse::Error::InvalidFileType { r#type: std::any::type_name::<MftHeader>(), expected: MFT_MAGIC, actual: mft_header.magic }.into())
+		}
+
+		let num_entries = mft_header.num_entries as usize;
+		let entry_size = MftEntry::BINARY_SIZE.actual_size(false).unwrap();
+		let mut entries = Vec::with_capacity(std::cmp::min(num_entries, input.remaining.len() / entry_size + 1));
+		entries.push(MftEntry { offset: 0, size: 0, compression_flag: 0, entry_flag: 0, counter: 0, crc: 0 });
+		for _ in 1..num_entries {
+			entries.push(MftEntry::parse(input)?);
+		}
+
+		let mut me = Self { reader, header, mft_header, entries, file_ids: HashMap::new() };
+
+		let id_table = me.read_entry(FILE_ID_TABLE_INDEX)?;
+		let input = &mut Input::new(&id_table, false);
+		while !input.remaining.is_empty() {
+			let FileIdEntry { file_id, mft_index } = FileIdEntry::parse(input)?;
+			if file_id == 0 || mft_index == 0 { continue }
+			me.file_ids.insert(file_id, mft_index as usize);
+		}
+
+		Ok(me)
+	}
+
+	pub fn entries(&self) -> &[MftEntry] { &self.entries }
+
+	pub fn file_ids(&self) -> impl Iterator<Item = u32> + '_ { self.file_ids.keys().copied() }
+
+	pub fn entry_index(&self, file_id : u32) -> Option<usize> { self.file_ids.get(&file_id).copied() }
+
+	pub fn entry(&self, file_id : u32) -> Option<&MftEntry> {
+		self.entry_index(file_id).and_then(|i| self.entries.get(i))
+	}
+
+	/// Reads the contents of the file with the given id, ready to be passed to [`crate::pf::PackFileReader::from_bytes`].
+	pub fn read_file(&mut self, file_id : u32) -> Result<Vec<u8>> {
+		let index = self.entry_index(file_id).ok_or(Error::FileNotFound { file_id })?;
+		self.read_entry(index)
+	}
+
+	pub fn read_entry(&mut self, index : usize) -> Result<Vec<u8>> {
+		let Some(entry) = self.entries.get(index).filter(|_| index > 0) else {
+			return Err(Error::EntryOutOfBounds { index, num_entries: self.entries.len() })
+		};
+		let compressed = entry.is_compressed();
+
+		let data = read_bytes(&mut self.reader, entry.offset, entry.size as usize)?;
+		if compressed { Ok(crate::compression::inflate(&data)?) }
+		else { Ok(data) }
+	}
+}
+
+fn read_bytes(reader : &mut (impl Read + Seek), offset : u64, size : usize) -> Result<Vec<u8>> {
+	reader.seek(SeekFrom::Start(offset))?;
+	let mut buffer = Vec::new();
+	reader.take(size as u64).read_to_end(&mut buffer)?;
+	if buffer.len() < size {
+		return Err(crate::parse::Error::DataTooShort { r#type: None, required: size, actual: buffer.len() }.into())
+	}
+	Ok(buffer)
+}
+
+fn read_struct<T : for<'inp> Parse<'inp>>(reader : &mut (impl Read + Seek), offset : u64) -> Result<T> {
+	let size = T::BINARY_SIZE.actual_size(false).unwrap();
+	let buffer = read_bytes(reader, offset, size)?;
+	Ok(T::parse(&mut Input::new(&buffer, false))?)
+}
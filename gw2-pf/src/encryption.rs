@@ -0,0 +1,99 @@
+//! Decryption of encrypted audio payloads.
+//!
+//! The data is encrypted with RC4. The RC4 key is derived from a 64 bit key through five rounds of SHA-1, see [`derive_rc4_key`].
+//! The 64 bit keys are not part of the archive and have to be provided by the caller.
+
+use crate::parse::{Error, Result};
+
+/// Decrypts `data` with the 64 bit `key`. A key of 0 is the same as no key.
+pub fn decrypt(data : &[u8], key : Option<u64>) -> Result<Vec<u8>> {
+	let mut output = data.to_vec();
+	decrypt_in_place(&mut output, key)?;
+	Ok(output)
+}
+
+/// Same as [`decrypt`], but overwrites `data`.
+pub fn decrypt_in_place(data : &mut [u8], key : Option<u64>) -> Result<()> {
+	let key = match key {
+		Some(0) | None => return Err(Error::MissingDecryptionKey),
+		Some(key) => key,
+	};
+	Rc4::new(&derive_rc4_key(key)).apply(data);
+	Ok(())
+}
+
+/// The RC4 key for a 64 bit key: the first five rounds of a single SHA-1 block filled by repeating the little endian key.
+#[allow(clippy::identity_op)]
+pub fn derive_rc4_key(key : u64) -> [u8; 20] {
+	let buffer = key.to_le_bytes();
+	let mut block = [0u32; 5];
+	for (i, word) in block.iter_mut().enumerate() {
+		*word = u32::from_le_bytes([
+			buffer[(0 + i * 4) % buffer.len()],
+			buffer[(1 + i * 4) % buffer.len()],
+			buffer[(2 + i * 4) % buffer.len()],
+			buffer[(3 + i * 4) % buffer.len()],
+		]);
+	}
+
+	let [mut a, mut b, mut c, mut d, mut e] = [0x67452301u32, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+
+	macro_rules! round {
+		($b:expr, $v:expr, $w:expr, $x:expr, $y:expr, $z:expr) => {{
+			$z = $z
+				.wrapping_add(($w & ($x ^ $y)) ^ $y)
+				.wrapping_add($b)
+				.wrapping_add(0x5a827999)
+				.wrapping_add(u32::rotate_left($v, 5));
+			$w = u32::rotate_left($w, 30);
+		}};
+	}
+	round!(block[0], a, b, c, d, e);
+	round!(block[1], e, a, b, c, d);
+	round!(block[2], d, e, a, b, c);
+	round!(block[3], c, d, e, a, b);
+	round!(block[4], b, c, d, e, a);
+
+	let digest = [a.wrapping_add(block[0]), b.wrapping_add(block[1]), c.wrapping_add(block[2]), d.wrapping_add(block[3]), e.wrapping_add(block[4])];
+	let mut rc4_key = [0u8; 20];
+	for (bytes, word) in rc4_key.chunks_exact_mut(4).zip(digest) {
+		bytes.copy_from_slice(&word.to_le_bytes());
+	}
+	rc4_key
+}
+
+/// Plain RC4 stream cipher, encryption and decryption are the same operation.
+pub struct Rc4 {
+	state : [u8; 256],
+	i     : u8,
+	j     : u8,
+}
+
+impl Rc4 {
+	/// `key` has to be between 1 and 256 bytes long.
+	pub fn new(key : &[u8]) -> Self {
+		assert!(!key.is_empty() && key.len() <= 256, "RC4 keys have to be between 1 and 256 bytes long");
+
+		let mut state = [0u8; 256];
+		for (i, s) in state.iter_mut().enumerate() { *s = i as u8 }
+
+		let mut j = 0u8;
+		for i in 0..256 {
+			j = j.wrapping_add(state[i]).wrapping_add(key[i % key.len()]);
+			state.swap(i, j as usize);
+		}
+
+		Self { state, i: 0, j: 0 }
+	}
+
+	/// XORs the key stream into `data`.
+	pub fn apply(&mut self, data : &mut [u8]) {
+		for byte in data {
+			self.i = self.i.wrapping_add(1);
+			self.j = self.j.wrapping_add(self.state[self.i as usize]);
+			self.state.swap(self.i as usize, self.j as usize);
+			let k = self.state[self.state[self.i as usize].wrapping_add(self.state[self.j as usize]) as usize];
+			*byte ^= k;
+		}
+	}
+}
@@ -0,0 +1,178 @@
+//! Conversion of audio payloads into files that common tools can play.
+
+use crate::{formats::{abnk::bkck::v2::ASNDFile, asnd::asnd::v2::{PayloadKind, WaveformData}}, parse::{Error, Result}};
+
+/// Wraps `audio_data` into a RIFF/WAVE file, treating it as raw samples.
+///
+/// No [`crate::formats::asnd::asnd::v2::AudioFormat`] value for uncompressed audio is known yet, so the caller has to know that the data is raw PCM.
+/// The input is not validated beyond that: payloads with a recognizable container are rejected and the size has to fit the sample and channel count,
+/// anything else is written out as samples, even if it is compressed or encrypted.
+///
+/// The samples are 16 bit, the sample rate is derived from `num_samples` and `length`.
+/// A channel count of 0 is treated as mono. Loops are written into a `smpl` chunk, `loop_end` is treated as exclusive.
+pub fn wav(waveform : &WaveformData) -> Result<Vec<u8>> {
+	const BITS_PER_SAMPLE : u32 = 16;
+
+	if PayloadKind::detect(waveform.audio_data).is_some() { return Err(Error::UnsupportedAudio { reason: "the payload is not raw PCM" }) }
+
+	let channels = waveform.num_channels.max(1) as u32;
+	let sample_rate = waveform.sample_rate().ok_or(Error::UnsupportedAudio { reason: "the waveform has no valid length or sample count" })?;
+	let block_align = channels * BITS_PER_SAMPLE / 8;
+	let byte_rate = sample_rate.checked_mul(block_align).ok_or(Error::UnsupportedAudio { reason: "the byte rate does not fit into 32 bits" })?;
+	let data = waveform.audio_data;
+	if (waveform.num_samples as usize).checked_mul(block_align as usize) != Some(data.len()) {
+		return Err(Error::UnsupportedAudio { reason: "the data size does not match 16 bit samples for the sample and channel count" })
+	}
+
+	let mut fmt = Vec::with_capacity(16);
+	fmt.extend_from_slice(&1u16.to_le_bytes()); // WAVE_FORMAT_PCM
+	fmt.extend_from_slice(&(channels as u16).to_le_bytes());
+	fmt.extend_from_slice(&sample_rate.to_le_bytes());
+	fmt.extend_from_slice(&byte_rate.to_le_bytes());
+	fmt.extend_from_slice(&(block_align as u16).to_le_bytes());
+	fmt.extend_from_slice(&(BITS_PER_SAMPLE as u16).to_le_bytes());
+
+	let mut chunks = Vec::with_capacity(data.len() + 128);
+	write_riff_chunk(&mut chunks, b"fmt ", &fmt)?;
+	if waveform.has_loop() {
+		write_riff_chunk(&mut chunks, b"smpl", &smpl_chunk(sample_rate, waveform.loop_start, waveform.loop_end - 1))?;
+	}
+	write_riff_chunk(&mut chunks, b"data", data)?;
+
+	let mut file = Vec::with_capacity(chunks.len() + 12);
+	file.extend_from_slice(b"RIFF");
+	file.extend_from_slice(&riff_size(chunks.len() + 4)?.to_le_bytes());
+	file.extend_from_slice(b"WAVE");
+	file.extend_from_slice(&chunks);
+	Ok(file)
+}
+
+/// Sampler chunk with a single forward loop, `end` is inclusive.
+fn smpl_chunk(sample_rate : u32, start : u32, end : u32) -> Vec<u8> {
+	let fields : [u32; 15] = [
+		0,                           // manufacturer
+		0,                           // product
+		1_000_000_000 / sample_rate, // sample period in nanoseconds
+		60,                          // midi unity note, middle C
+		0,                           // midi pitch fraction
+		0,                           // smpte format
+		0,                           // smpte offset
+		1,                           // number of loops
+		0,                           // size of the sampler specific data
+		0,                           // loop cue point id
+		0,                           // loop type, 0 is forward
+		start,
+		end,
+		0,                           // loop fraction
+		0,                           // loop play count, 0 is infinite
+	];
+	fields.iter().flat_map(|f| f.to_le_bytes()).collect()
+}
+
+fn write_riff_chunk(output : &mut Vec<u8>, id : &[u8; 4], data : &[u8]) -> Result<()> {
+	output.extend_from_slice(id);
+	output.extend_from_slice(&riff_size(data.len())?.to_le_bytes());
+	output.extend_from_slice(data);
+	// chunks are aligned to 2 bytes
+	if !data.len().is_multiple_of(2) { output.push(0) }
+	Ok(())
+}
+
+fn riff_size(size : usize) -> Result<u32> {
+	u32::try_from(size).map_err(|_| Error::ValueTooLarge { r#type: "RIFF chunk size", actual: size, max: u32::MAX as usize })
+}
+
+/// Timing and loop information of a waveform that is lost when only the payload is written out.
+/// Positions are in samples, times in seconds.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AudioMetadata {
+	pub voice_id     : Option<u32>,
+	pub sample_rate  : Option<u32>,
+	pub num_channels : u8,
+	pub num_samples  : u32,
+	pub length       : f32,
+	pub offset       : f32,
+	/// `None` if the waveform does not loop.
+	pub loop_start   : Option<u32>,
+	/// Exclusive, `None` if the waveform does not loop.
+	pub loop_end     : Option<u32>,
+	/// Samples from the loop start to the loop end, `None` if the waveform does not loop.
+	pub loop_length  : Option<u32>,
+	/// Length stored in the bank entry, see [`AudioMetadata::with_bank_entry`].
+	pub bank_length  : Option<f32>,
+	/// Offset stored in the bank entry, see [`AudioMetadata::with_bank_entry`].
+	pub bank_offset  : Option<f32>,
+}
+
+impl AudioMetadata {
+	pub fn from_waveform(waveform : &WaveformData) -> Self {
+		let (loop_start, loop_end, loop_length) = match waveform.has_loop() {
+			true  => (Some(waveform.loop_start), Some(waveform.loop_end), Some(waveform.loop_end - waveform.loop_start)),
+			false => (None, None, None),
+		};
+		Self {
+			voice_id    : None,
+			sample_rate : waveform.sample_rate(),
+			num_channels: waveform.num_channels,
+			num_samples : waveform.num_samples,
+			length      : waveform.length,
+			offset      : waveform.offset,
+			loop_start,
+			loop_end,
+			loop_length,
+			bank_length : None,
+			bank_offset : None,
+		}
+	}
+
+	/// Adds the values of the ABNK entry the waveform was stored in.
+	pub fn with_bank_entry(mut self, entry : &ASNDFile) -> Self {
+		self.voice_id = Some(entry.voice_id);
+		self.bank_length = Some(entry.length);
+		self.bank_offset = Some(entry.offset);
+		self
+	}
+}
+
+/// Prepends an ID3v2.3 tag with the metadata to an mp3 payload.
+///
+/// Loops are written as `LOOPSTART` and `LOOPLENGTH` user text frames in samples, the way many game engines and loop aware players read them.
+/// The duration is written as a `TLEN` frame in milliseconds.
+pub fn mp3_with_metadata(payload : &[u8], metadata : &AudioMetadata) -> Result<Vec<u8>> {
+	match PayloadKind::detect(payload) {
+		Some(PayloadKind::Mp3) if !payload.starts_with(b"ID3") => {},
+		Some(PayloadKind::Mp3) => return Err(Error::UnsupportedAudio { reason: "the payload already has an ID3 tag" }),
+		_ => return Err(Error::UnsupportedAudio { reason: "the payload is not mp3" }),
+	}
+
+	let mut frames = Vec::new();
+	if metadata.length.is_finite() && metadata.length > 0.0 {
+		write_id3_frame(&mut frames, b"TLEN", &[&((metadata.length as f64 * 1000.0).round() as u64).to_string()])?;
+	}
+	if let (Some(start), Some(length)) = (metadata.loop_start, metadata.loop_length) {
+		write_id3_frame(&mut frames, b"TXXX", &["LOOPSTART", &start.to_string()])?;
+		write_id3_frame(&mut frames, b"TXXX", &["LOOPLENGTH", &length.to_string()])?;
+	}
+
+	// tag sizes are "syncsafe", only the lower 7 bits of each byte are used
+	if frames.len() >= 1 << 28 { return Err(Error::ValueTooLarge { r#type: "ID3 tag", actual: frames.len(), max: (1 << 28) - 1 }) }
+	let size = frames.len() as u32;
+	let mut file = Vec::with_capacity(10 + frames.len() + payload.len());
+	file.extend_from_slice(b"ID3\x03\x00\x00");
+	file.extend_from_slice(&[(size >> 21) as u8 & 0x7f, (size >> 14) as u8 & 0x7f, (size >> 7) as u8 & 0x7f, size as u8 & 0x7f]);
+	file.extend_from_slice(&frames);
+	file.extend_from_slice(payload);
+	Ok(file)
+}
+
+/// Text frame with ISO-8859-1 encoding, multiple strings are separated by a terminator.
+fn write_id3_frame(output : &mut Vec<u8>, id : &[u8; 4], strings : &[&str]) -> Result<()> {
+	let content = strings.join("\0");
+	let size = u32::try_from(content.len() + 1).map_err(|_| Error::ValueTooLarge { r#type: "ID3 frame size", actual: content.len() + 1, max: u32::MAX as usize })?;
+	output.extend_from_slice(id);
+	output.extend_from_slice(&size.to_be_bytes());
+	output.extend_from_slice(&[0, 0, 0]); // flags, encoding
+	output.extend_from_slice(content.as_bytes());
+	Ok(())
+}
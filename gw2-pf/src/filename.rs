@@ -1,13 +1,99 @@
-use crate::{parse::{BinarySize, Result, Input, Parse}, wstr::WString};
+use std::{cmp::Ordering, ffi::OsString};
+use crate::{parse::{BinarySize, Result, Input, Parse}, serialize::{Output, Serialize}, wstr::{WStr, WString}};
 
-#[derive(Debug)]
+/// Reference to another file of the archive, stored as a wide string.
+///
+/// Almost all file names are file ids packed into exactly two characters, see [`FileName::from_id`] and [`FileName::kind`].
+/// Empty names are used for "no file" and are treated as id 0.
+///
+/// Names are ordered by [`FileName::kind`], so packed ids sort by their numeric value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct FileName(WString);
 
+/// What a [`FileName`] refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum FileNameKind {
+	/// The name is empty.
+	NoFile,
+	/// Two characters encoding a file id.
+	Id(u32),
+	/// Anything else, the text has to be interpreted by the caller.
+	Other,
+}
+
+/// Both characters of a packed id are offset by this, so they never contain a zero byte.
+const PACKED_CHAR_OFFSET : u32 = 0x100;
+/// Values per character of a packed id.
+const PACKED_CHAR_RANGE : u32 = 0xff00;
+
+impl FileName {
+	/// The largest id that can be packed into a file name.
+	pub const MAX_ID : u32 = PACKED_CHAR_RANGE * PACKED_CHAR_RANGE;
+
+	/// Packs `id` into two characters, id 0 gives the empty name. `None` if the id is larger than [`FileName::MAX_ID`].
+	pub fn from_id(id : u32) -> Option<Self> {
+		let chars = match id {
+			0 => Vec::new(),
+			id => packed_chars(id)?.to_vec(),
+		};
+		Some(Self(WString::from(chars)))
+	}
+
+	pub fn kind(&self) -> FileNameKind { kind_from_chars(self.iter().copied()) }
+
+	/// The packed file id, 0 for empty names and names that are not a packed id. Inverse of [`FileName::from_id`].
+	pub fn to_id(&self) -> u32 { self.kind().id().unwrap_or(0) }
+
+	/// Invalid surrogates are replaced with U+FFFD.
+	pub fn to_string_lossy(&self) -> String { String::from_utf16_lossy(&self.0) }
+}
+
+impl FileNameKind {
+	/// The file id, 0 for [`FileNameKind::NoFile`].
+	pub fn id(&self) -> Option<u32> {
+		match *self {
+			FileNameKind::NoFile => Some(0),
+			FileNameKind::Id(id) => Some(id),
+			FileNameKind::Other  => None,
+		}
+	}
+}
+
+/// The two characters of a non zero packed id.
+pub(crate) fn packed_chars(id : u32) -> Option<[u16; 2]> {
+	if id == 0 || id > FileName::MAX_ID { return None }
+	let value = id - 1;
+	Some([(PACKED_CHAR_OFFSET + value % PACKED_CHAR_RANGE) as u16, (PACKED_CHAR_OFFSET + value / PACKED_CHAR_RANGE) as u16])
+}
+
+pub(crate) fn kind_from_chars(mut chars : impl Iterator<Item = u16>) -> FileNameKind {
+	match (chars.next(), chars.next(), chars.next()) {
+		(None, _, _) => FileNameKind::NoFile,
+		(Some(low), Some(high), None) if low as u32 >= PACKED_CHAR_OFFSET && high as u32 >= PACKED_CHAR_OFFSET => {
+			FileNameKind::Id((high as u32 - PACKED_CHAR_OFFSET) * PACKED_CHAR_RANGE + (low as u32 - PACKED_CHAR_OFFSET) + 1)
+		},
+		_ => FileNameKind::Other,
+	}
+}
+
+/// Ids are shown as decimal numbers, other names as their text.
+fn display_name(kind : FileNameKind, text : impl std::fmt::Display, f : &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+	match kind {
+		FileNameKind::NoFile => Ok(()),
+		FileNameKind::Id(id) => write!(f, "{id}"),
+		FileNameKind::Other  => write!(f, "{text}"),
+	}
+}
+
 impl<'inp> Parse<'inp> for FileName {
 	const BINARY_SIZE : BinarySize = WString::BINARY_SIZE;
 	fn parse(input : &mut Input<'inp>) -> Result<Self> { WString::parse(input).map(Self) }
 }
 
+impl Serialize for FileName {
+	fn serialize(&self, output : &mut Output) -> Result<()> { self.0.serialize(output) }
+}
+
 impl std::ops::Deref for FileName {
 	type Target = WString;
 	fn deref(&self) -> &Self::Target { &self.0 }
@@ -16,12 +102,111 @@ impl std::ops::DerefMut for FileName {
 	fn deref_mut(&mut self) -> &mut Self::Target { &mut self.0 }
 }
 
-impl FileName {
-	pub fn to_id(&self) -> u32 {
-		if self.len() < 2 {
-			0
-		} else {
-			((self[1] as i32 * 0xff00) + (self[0] as i32 - 0xff00ff)) as u32
+impl std::fmt::Display for FileName {
+	fn fmt(&self, f : &mut std::fmt::Formatter<'_>) -> std::fmt::Result { display_name(self.kind(), self.to_string_lossy(), f) }
+}
+
+impl Ord for FileName {
+	fn cmp(&self, other : &Self) -> Ordering { self.kind().cmp(&other.kind()).then_with(|| self.0.cmp(&other.0)) }
+}
+impl PartialOrd for FileName {
+	fn partial_cmp(&self, other : &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl From<WString> for FileName {
+	fn from(value : WString) -> Self { Self(value) }
+}
+
+impl From<FileNameRef<'_>> for FileName {
+	fn from(value : FileNameRef<'_>) -> Self { Self(value.0.into()) }
+}
+
+/// Ids are written as numbers (0 for no file), other names as strings.
+#[cfg(feature = "serde")]
+impl serde::Serialize for FileName {
+	fn serialize<S : serde::Serializer>(&self, serializer : S) -> std::result::Result<S::Ok, S::Error> {
+		match self.kind().id() {
+			Some(id) => serializer.serialize_u32(id),
+			None     => serde::Serialize::serialize(&self.0, serializer),
+		}
+	}
+}
+
+/// Accepts the output of the `Serialize` implementation.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FileName {
+	fn deserialize<D : serde::Deserializer<'de>>(deserializer : D) -> std::result::Result<Self, D::Error> {
+		struct Visitor;
+		impl serde::de::Visitor<'_> for Visitor {
+			type Value = FileName;
+
+			fn expecting(&self, f : &mut std::fmt::Formatter) -> std::fmt::Result { f.write_str("a file id or a file name") }
+
+			fn visit_u64<E : serde::de::Error>(self, value : u64) -> std::result::Result<FileName, E> {
+				u32::try_from(value).ok().and_then(FileName::from_id)
+					.ok_or_else(|| E::invalid_value(serde::de::Unexpected::Unsigned(value), &"a file id up to FileName::MAX_ID"))
+			}
+
+			fn visit_str<E : serde::de::Error>(self, value : &str) -> std::result::Result<FileName, E> {
+				Ok(FileName(WString::from(value.encode_utf16().collect::<Vec<_>>())))
+			}
+		}
+
+		deserializer.deserialize_any(Visitor)
+	}
+}
+
+/// Borrowed counterpart to [`FileName`] that points into the input instead of copying it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FileNameRef<'inp>(WStr<'inp>);
+
+impl<'inp> Parse<'inp> for FileNameRef<'inp> {
+	const BINARY_SIZE : BinarySize = WStr::BINARY_SIZE;
+	fn parse(input : &mut Input<'inp>) -> Result<Self> { WStr::parse(input).map(Self) }
+}
+
+impl Serialize for FileNameRef<'_> {
+	fn serialize(&self, output : &mut Output) -> Result<()> { self.0.serialize(output) }
+}
+
+impl<'inp> std::ops::Deref for FileNameRef<'inp> {
+	type Target = WStr<'inp>;
+	fn deref(&self) -> &Self::Target { &self.0 }
+}
+
+impl FileNameRef<'_> {
+	pub fn kind(&self) -> FileNameKind { kind_from_chars(self.chars()) }
+
+	/// See [`FileName::to_id`].
+	pub fn to_id(&self) -> u32 { self.kind().id().unwrap_or(0) }
+}
+
+impl std::fmt::Display for FileNameRef<'_> {
+	fn fmt(&self, f : &mut std::fmt::Formatter<'_>) -> std::fmt::Result { display_name(self.kind(), self.0, f) }
+}
+
+impl Ord for FileNameRef<'_> {
+	fn cmp(&self, other : &Self) -> Ordering { self.kind().cmp(&other.kind()).then_with(|| self.chars().cmp(other.chars())) }
+}
+impl PartialOrd for FileNameRef<'_> {
+	fn partial_cmp(&self, other : &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl From<FileNameRef<'_>> for String {
+	fn from(value : FileNameRef<'_>) -> Self { value.to_string_lossy() }
+}
+
+impl From<FileNameRef<'_>> for OsString {
+	fn from(value : FileNameRef<'_>) -> Self { value.to_os_string() }
+}
+
+/// Same representation as [`FileName`].
+#[cfg(feature = "serde")]
+impl serde::Serialize for FileNameRef<'_> {
+	fn serialize<S : serde::Serializer>(&self, serializer : S) -> std::result::Result<S::Ok, S::Error> {
+		match self.kind().id() {
+			Some(id) => serializer.serialize_u32(id),
+			None     => serde::Serialize::serialize(&self.0, serializer),
 		}
 	}
 }
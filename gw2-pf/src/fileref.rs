@@ -0,0 +1,70 @@
+use crate::{filename::{kind_from_chars, packed_chars}, parse::{BinarySize, Error, Input, Parse, Result}, serialize::{Output, Serialize}, FileName, FileNameKind};
+
+/// Inline counterpart to [`FileName`]: up to two characters and a terminator stored directly in the struct instead of behind a pointer.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct FileRef(pub [u16; 3]);
+
+impl FileRef {
+	/// Same encoding as [`FileName::from_id`].
+	pub fn from_id(id : u32) -> Option<Self> {
+		match id {
+			0 => Some(Self::default()),
+			id => packed_chars(id).map(|[low, high]| Self([low, high, 0])),
+		}
+	}
+
+	/// The characters up to the first terminator.
+	pub fn chars(&self) -> impl Iterator<Item = u16> + '_ {
+		self.0.iter().copied().take_while(|c| *c != 0)
+	}
+
+	pub fn kind(&self) -> FileNameKind { kind_from_chars(self.chars()) }
+
+	/// See [`FileName::to_id`].
+	pub fn to_id(&self) -> u32 { self.kind().id().unwrap_or(0) }
+
+	pub fn to_file_name(&self) -> FileName { FileName::from(crate::WString::from(self.chars().collect::<Vec<_>>())) }
+}
+
+impl<'inp> Parse<'inp> for FileRef {
+	const BINARY_SIZE : BinarySize = BinarySize::fixed(6);
+	fn parse(input : &mut Input<'inp>) -> Result<Self> {
+		if input.remaining.len() < 6 { return Err(Error::to_short::<Self>(input.remaining.len())) }
+		Ok(Self([u16::parse(input)?, u16::parse(input)?, u16::parse(input)?]))
+	}
+}
+
+impl Serialize for FileRef {
+	fn serialize(&self, output : &mut Output) -> Result<()> {
+		for c in self.0 { c.serialize(output)? }
+		Ok(())
+	}
+}
+
+impl std::fmt::Display for FileRef {
+	fn fmt(&self, f : &mut std::fmt::Formatter<'_>) -> std::fmt::Result { self.to_file_name().fmt(f) }
+}
+
+impl std::fmt::Debug for FileRef {
+	fn fmt(&self, f : &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.kind() {
+			FileNameKind::Id(id) => write!(f, "FileRef({id})"),
+			_                    => write!(f, "FileRef({:x?})", self.0),
+		}
+	}
+}
+
+impl Ord for FileRef {
+	fn cmp(&self, other : &Self) -> std::cmp::Ordering { self.kind().cmp(&other.kind()).then_with(|| self.0.cmp(&other.0)) }
+}
+impl PartialOrd for FileRef {
+	fn partial_cmp(&self, other : &Self) -> Option<std::cmp::Ordering> { Some(self.cmp(other)) }
+}
+
+/// Same representation as [`FileName`].
+#[cfg(feature = "serde")]
+impl serde::Serialize for FileRef {
+	fn serialize<S : serde::Serializer>(&self, serializer : S) -> std::result::Result<S::Ok, S::Error> {
+		serde::Serialize::serialize(&self.to_file_name(), serializer)
+	}
+}
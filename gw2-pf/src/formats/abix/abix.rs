@@ -2,8 +2,9 @@
 pub mod bidx;
 
 
-#[derive(crate::Parse)]
+#[derive(Debug, crate::Parse)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[packfile]
-pub enum ABIX {
-	BIDX(bidx::BIDX),
+pub enum ABIX<'a> {
+	BIDX(bidx::BIDX<'a>),
 }
@@ -1,7 +1,8 @@
 pub mod v0;
 
 #[derive(Debug, crate::Parse)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[chunk]
-pub enum BIDX {
-	#[v(0)] V0(v0::BankIndexData),
+pub enum BIDX<'a> {
+	#[v(0)] V0(v0::BankIndexData<'a>),
 }
@@ -1,16 +1,20 @@
-use crate::FileName;
+use crate::{FileNameRef, LazyArray};
 
 #[derive(Debug, crate::Parse)]
-pub struct BankIndexData {
-	pub bank_language: Vec<BankLanguageData>,
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct BankIndexData<'a> {
+	pub bank_language: Vec<BankLanguageData<'a>>,
 }
 
 #[derive(Debug, crate::Parse)]
-pub struct BankLanguageData {
-	pub bank_file_name: Vec<BankFileNameData>,
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct BankLanguageData<'a> {
+	//NOTE(Rennorb): Tens of thousands of entries per language, usually only a single one is looked up.
+	pub bank_file_name: LazyArray<'a, BankFileNameData<'a>>,
 }
 
 #[derive(Debug, crate::Parse)]
-pub struct BankFileNameData {
-	pub file_name: Option<FileName>,
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct BankFileNameData<'a> {
+	pub file_name: Option<FileNameRef<'a>>,
 }
@@ -1,8 +1,85 @@
 #[path = "bkck/bkck.rs"]
 pub mod bkck;
 
+use crate::{formats::asnd::{self, asnd::v2::{PayloadKind, WaveformData}}, parse::{ChunkIter, Error}, pf::PackFileReader};
+
 #[derive(Debug, crate::Parse)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[packfile]
 pub enum ABNK<'a> {
 	BKCK(bkck::BKCK<'a>),
 }
+
+impl<'a> ABNK<'a> {
+	/// All waveforms of the bank, read from the ASND files nested in its entries. Entries without audio data are skipped.
+	///
+	/// Entries that fail to parse are reported as errors, the following entries are still read.
+	pub fn waveforms(&self) -> BankWaveforms<'_, 'a> {
+		BankWaveforms { files: self.files.iter(), current: None }
+	}
+}
+
+/// One waveform of a bank, see [`ABNK::waveforms`].
+#[derive(Debug)]
+pub struct BankWaveform<'b, 'a> {
+	pub voice_id     : u32,
+	/// Position of the waveform in the ASND file of the entry.
+	pub index        : usize,
+	pub entry        : &'b bkck::v2::ASNDFile<'a>,
+	pub waveform     : WaveformData<'a>,
+	pub payload_kind : PayloadKind,
+}
+
+/// A bank entry or one of its waveforms could not be read.
+#[derive(Debug)]
+pub struct BankEntryError {
+	pub voice_id : u32,
+	/// Position of the waveform in the ASND file, `None` if the file itself could not be opened.
+	pub index    : Option<usize>,
+	pub error    : Error,
+}
+
+impl std::fmt::Display for BankEntryError {
+	fn fmt(&self, f : &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.index {
+			Some(index) => write!(f, "voice {} waveform {index}: {}", self.voice_id, self.error),
+			None        => write!(f, "voice {}: {}", self.voice_id, self.error),
+		}
+	}
+}
+
+impl std::error::Error for BankEntryError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> { Some(&self.error) }
+}
+
+pub struct BankWaveforms<'b, 'a> {
+	files   : std::slice::Iter<'b, bkck::v2::ASNDFile<'a>>,
+	/// The entry that is currently being read, with the waveforms that are left in it.
+	current : Option<(&'b bkck::v2::ASNDFile<'a>, std::iter::Enumerate<ChunkIter<'a, asnd::ASND<'a>>>)>,
+}
+
+impl<'b, 'a> Iterator for BankWaveforms<'b, 'a> {
+	type Item = Result<BankWaveform<'b, 'a>, BankEntryError>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			if let Some((entry, chunks)) = &mut self.current {
+				match chunks.next() {
+					Some((index, Ok(asnd::ASND::ASND(asnd::asnd::ASND::V2(waveform))))) => {
+						let payload_kind = waveform.detect_payload_kind();
+						return Some(Ok(BankWaveform { voice_id: entry.voice_id, index, entry, waveform, payload_kind }))
+					},
+					Some((index, Err(error))) => return Some(Err(BankEntryError { voice_id: entry.voice_id, index: Some(index), error })),
+					None => self.current = None,
+				}
+			}
+
+			let entry = self.files.next()?;
+			if entry.audio_data.is_empty() { continue }
+			match PackFileReader::<asnd::ASND>::from_bytes(entry.audio_data) {
+				Ok(chunks) => self.current = Some((entry, chunks.enumerate())),
+				Err(error) => return Some(Err(BankEntryError { voice_id: entry.voice_id, index: None, error })),
+			}
+		}
+	}
+}
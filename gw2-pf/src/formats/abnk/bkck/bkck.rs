@@ -1,6 +1,7 @@
 pub mod v2;
 
 #[derive(Debug, crate::Parse)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[chunk]
 pub enum BKCK<'a> {
 	#[v(2)] V2(v2::BankFileData<'a>),
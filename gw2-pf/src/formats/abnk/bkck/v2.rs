@@ -1,4 +1,5 @@
 #[derive(Debug, crate::Parse)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct BankFileData<'a> {
 	   _reserved1     : u32,
 	   _reserved2     : u32,
@@ -9,6 +10,7 @@ pub struct BankFileData<'a> {
 }
 
 #[derive(Debug, crate::Parse)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ASNDFile<'a> {
 	pub voice_id   : u32,
 	pub flags      : u32,
@@ -1,7 +1,9 @@
 #[path = "asnd/asnd.rs"]
+#[allow(clippy::module_inception)]
 pub mod asnd;
 
 #[derive(Debug, crate::Parse)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[packfile]
 pub enum ASND<'a> {
 	ASND(asnd::ASND<'a>),
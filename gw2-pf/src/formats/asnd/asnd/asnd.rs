@@ -1,6 +1,7 @@
 pub mod v2;
 
 #[derive(Debug, crate::Parse)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[chunk]
 pub enum ASND<'a> {
 	#[v(2)]
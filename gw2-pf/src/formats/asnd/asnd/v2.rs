@@ -1,4 +1,5 @@
 #[derive(Debug, crate::Parse)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct WaveformData<'a> {
 	pub length        : f32,
 	pub offset        : f32,
@@ -21,3 +22,129 @@ pub struct WaveformData<'a> {
 	pub audio_data    : &'a [u8],
 	pub other_data    : &'a [u8],
 }
+
+impl WaveformData<'_> {
+	pub fn audio_format(&self) -> AudioFormat { AudioFormat::from(self.format) }
+
+	/// Whether `loop_start..loop_end` describes a loop.
+	pub fn has_loop(&self) -> bool { self.loop_end > self.loop_start }
+
+	/// Samples per second, derived from `num_samples` and `length`. `None` if either is 0 or the length is invalid.
+	pub fn sample_rate(&self) -> Option<u32> {
+		if self.num_samples == 0 || !self.length.is_finite() || self.length <= 0.0 { return None }
+		let rate = (self.num_samples as f64 / self.length as f64).round();
+		(rate >= 1.0 && rate <= u32::MAX as f64).then_some(rate as u32)
+	}
+
+	/// What `audio_data` contains, based on its first bytes.
+	///
+	/// Data without a recognizable header is [`PayloadKind::Encrypted`] if it doesn't match `crc`, and [`PayloadKind::Unknown`] otherwise.
+	pub fn detect_payload_kind(&self) -> PayloadKind {
+		match PayloadKind::detect(self.audio_data) {
+			Some(kind) => kind,
+			None if self.audio_data.is_empty() => PayloadKind::Empty,
+			None if !self.crc_matches() => PayloadKind::Encrypted,
+			None => PayloadKind::Unknown,
+		}
+	}
+
+	/// Whether `crc` is the CRC-32 of `audio_data`. Always true if `crc` is 0.
+	//NOTE(Rennorb): The crc is computed over the plain payload, so it doesn't match for encrypted waveforms.
+	pub fn crc_matches(&self) -> bool { self.crc == 0 || crc32(self.audio_data) == self.crc }
+
+	/// The audio data decrypted with `key`, see [`crate::encryption`].
+	/// Fails with [`crate::parse::Error::MissingDecryptionKey`] if the key is missing or 0.
+	pub fn decrypt_audio(&self, key : Option<u64>) -> crate::parse::Result<Vec<u8>> {
+		crate::encryption::decrypt(self.audio_data, key)
+	}
+}
+
+/// Values of [`WaveformData::format`].
+//NOTE(Rennorb): Only 1 (mp3) occurs in the files we have, including the encrypted ones.
+// Other values stay `Unknown` until their meaning is confirmed from the executable or sample files.
+// The same goes for the bits of `flags`, which are 0 in every file we have, so they are left raw.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum AudioFormat {
+	Mp3,
+	Unknown(u8),
+}
+
+impl From<u8> for AudioFormat {
+	fn from(value : u8) -> Self {
+		match value {
+			1 => AudioFormat::Mp3,
+			other => AudioFormat::Unknown(other),
+		}
+	}
+}
+
+impl From<AudioFormat> for u8 {
+	fn from(value : AudioFormat) -> Self {
+		match value {
+			AudioFormat::Mp3 => 1,
+			AudioFormat::Unknown(other) => other,
+		}
+	}
+}
+
+/// Content of [`WaveformData::audio_data`], see [`WaveformData::detect_payload_kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum PayloadKind {
+	Empty,
+	/// MPEG audio, optionally starting with an ID3 tag.
+	Mp3,
+	Ogg,
+	/// RIFF/WAVE container.
+	Wav,
+	/// Data that doesn't match its crc, needs to be decrypted first.
+	Encrypted,
+	/// Data without a recognizable header that does match its crc.
+	Unknown,
+}
+
+impl PayloadKind {
+	/// Detects the container from the magic bytes at the start of `data`, `None` if there are none.
+	pub fn detect(data : &[u8]) -> Option<Self> {
+		match data {
+			[b'O', b'g', b'g', b'S', ..] => Some(PayloadKind::Ogg),
+			[b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => Some(PayloadKind::Wav),
+			[b'I', b'D', b'3', ..] => Some(PayloadKind::Mp3),
+			[a, b, c, ..] if is_mpeg_frame_header([*a, *b, *c]) => Some(PayloadKind::Mp3),
+			_ => None,
+		}
+	}
+
+	/// File extension for the payload, `bin` for payloads that can't be used as is.
+	pub fn extension(&self) -> &'static str {
+		match self {
+			PayloadKind::Mp3 => "mp3",
+			PayloadKind::Ogg => "ogg",
+			PayloadKind::Wav => "wav",
+			PayloadKind::Empty | PayloadKind::Encrypted | PayloadKind::Unknown => "bin",
+		}
+	}
+}
+
+/// Frame sync followed by a valid version, layer, bitrate and sample rate.
+/// Checking more than the sync bits keeps encrypted data from being detected as mp3 by chance.
+fn is_mpeg_frame_header([a, b, c] : [u8; 3]) -> bool {
+	let version = (b >> 3) & 0b11;
+	let layer = (b >> 1) & 0b11;
+	let bitrate = c >> 4;
+	let sample_rate = (c >> 2) & 0b11;
+	a == 0xff && b & 0xe0 == 0xe0 && version != 0b01 && layer != 0b00 && bitrate != 0b1111 && sample_rate != 0b11
+}
+
+/// CRC-32 (IEEE, reflected) as used for [`WaveformData::crc`].
+fn crc32(data : &[u8]) -> u32 {
+	let mut crc = !0u32;
+	for byte in data {
+		crc ^= *byte as u32;
+		for _ in 0..8 {
+			crc = (crc >> 1) ^ (0xedb88320 & (crc & 1).wrapping_neg());
+		}
+	}
+	!crc
+}
@@ -1,8 +1,11 @@
 #[path = "txtv/txtv.rs"]
+#[allow(clippy::module_inception)]
 pub mod _txtv;
 
 #[derive(Debug, crate::Parse)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[packfile]
+#[allow(non_camel_case_types)]
 pub enum txtv {
 	txtv(_txtv::txtv),
 }
\ No newline at end of file
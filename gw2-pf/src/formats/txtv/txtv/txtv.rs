@@ -1,7 +1,9 @@
 pub mod v0;
 
 #[derive(Debug, crate::Parse)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[chunk]
+#[allow(non_camel_case_types)]
 pub enum txtv {
 	#[v(0)] V0(v0::TextPackVoices),
 }
\ No newline at end of file
@@ -1,10 +1,12 @@
 #[derive(Debug, crate::Parse)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TextPackVoices {
 	/// OG: voices
 	pub mappings : Vec<TextPackVoice>,
 }
 
 #[derive(Debug, crate::Parse)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TextPackVoice {
 	///  OG: textId
 	pub text_id  : u32,
@@ -0,0 +1,135 @@
+use crate::{parse::{BinarySize, Error, Input, Parse, Result}, serialize::{Output, Serialize}};
+
+/// Array that only reads its length and position when parsed, elements are parsed on access.
+/// Use it instead of `Vec<T>` for large arrays of which usually only a few elements are needed.
+///
+/// Elements with a fixed binary size are accessed in constant time, others have to be skipped over from the start.
+#[derive(Clone)]
+pub struct LazyArray<'inp, T> {
+	/// Positioned at the first element, `None` for empty arrays.
+	input  : Option<Input<'inp>>,
+	length : usize,
+	_p     : std::marker::PhantomData<fn() -> T>,
+}
+
+impl<'inp, T : Parse<'inp>> Parse<'inp> for LazyArray<'inp, T> {
+	const BINARY_SIZE : BinarySize = BinarySize{ fixed_bytes: 4, ptrs: 1, is_dynamic: false };
+	fn parse(input : &mut Input<'inp>) -> Result<Self> {
+		let length = u32::parse(input)? as usize;
+		let offset = input.eat_offset()?;
+		input.observe_array::<T>(length, offset);
+		if length == 0 { return Ok(Self { input: None, length, _p: std::marker::PhantomData }) }
+
+		let array_input = input.clone_with_array_offset(offset)?;
+		if let Some(element_size) = T::BINARY_SIZE.actual_size(input.is_64_bit) {
+			let required = length.saturating_mul(element_size);
+			if array_input.remaining.len() < required {
+				return Err(Error::DataTooShort { r#type: Some(std::any::type_name::<Self>()), required, actual: array_input.remaining.len() })
+			}
+		}
+
+		Ok(Self { input: Some(array_input), length, _p: std::marker::PhantomData })
+	}
+}
+
+impl<'inp, T : Parse<'inp>> LazyArray<'inp, T> {
+	pub fn len(&self) -> usize { self.length }
+	pub fn is_empty(&self) -> bool { self.length == 0 }
+
+	/// Parses the element at `index`, `None` if the index is out of bounds.
+	pub fn get(&self, index : usize) -> Option<Result<T>> {
+		if index >= self.length { return None }
+		let input = self.input.as_ref()?;
+
+		match T::BINARY_SIZE.actual_size(input.is_64_bit) {
+			Some(element_size) => {
+				// the length was checked against the data during parsing
+				let mut element_input = input.with_data(&input.remaining[index * element_size..]);
+				Some(element_input.parse_element(index, T::parse))
+			},
+			None => self.iter().nth(index),
+		}
+	}
+
+	pub fn iter(&self) -> LazyArrayIter<'inp, T> {
+		LazyArrayIter { input: self.input.clone(), index: 0, length: self.length, _p: std::marker::PhantomData }
+	}
+
+	/// Parses all elements.
+	pub fn to_vec(&self) -> Result<Vec<T>> {
+		if let Some(input) = &self.input { input.reserve_array::<T>(self.length)?; }
+		self.iter().collect()
+	}
+}
+
+pub struct LazyArrayIter<'inp, T> {
+	input  : Option<Input<'inp>>,
+	index  : usize,
+	length : usize,
+	_p     : std::marker::PhantomData<fn() -> T>,
+}
+
+impl<'inp, T : Parse<'inp>> Iterator for LazyArrayIter<'inp, T> {
+	type Item = Result<T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.index >= self.length { return None }
+		let input = self.input.as_mut()?;
+
+		let result = input.parse_element(self.index, T::parse);
+		self.index += 1;
+		// the position of the following elements is unknown after an error
+		if result.is_err() { self.index = self.length }
+		Some(result)
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(0, Some(self.length - self.index))
+	}
+}
+
+impl<'inp, T : Parse<'inp>> IntoIterator for &LazyArray<'inp, T> {
+	type Item = Result<T>;
+	type IntoIter = LazyArrayIter<'inp, T>;
+	fn into_iter(self) -> Self::IntoIter { self.iter() }
+}
+
+impl<'inp, T : Parse<'inp> + std::fmt::Debug> std::fmt::Debug for LazyArray<'inp, T> {
+	fn fmt(&self, f : &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let mut list = f.debug_list();
+		for element in self {
+			match element {
+				Ok(element) => list.entry(&element),
+				Err(e) => list.entry(&format_args!("<{e}>")),
+			};
+		}
+		list.finish()
+	}
+}
+
+impl<'inp, T : Parse<'inp> + Serialize> Serialize for LazyArray<'inp, T> {
+	fn serialize(&self, output : &mut Output) -> Result<()> {
+		output.write_length(self.len())?;
+		if self.is_empty() { output.write_null_pointer(); return Ok(()) }
+
+		output.write_pointer(|output| {
+			for element in self {
+				element?.serialize(output)?;
+			}
+			Ok(())
+		})
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'inp, T : Parse<'inp> + serde::Serialize> serde::Serialize for LazyArray<'inp, T> {
+	fn serialize<S : serde::Serializer>(&self, serializer : S) -> std::result::Result<S::Ok, S::Error> {
+		use serde::ser::{Error as _, SerializeSeq};
+
+		let mut seq = serializer.serialize_seq(Some(self.len()))?;
+		for element in self {
+			seq.serialize_element(&element.map_err(S::Error::custom)?)?;
+		}
+		seq.end()
+	}
+}
@@ -1,17 +1,28 @@
 pub mod pf;
 pub mod formats;
 pub mod parse;
+pub mod observe;
+pub mod serialize;
+pub mod dat;
+pub mod compression;
+pub mod encryption;
+pub mod export;
 
-mod wstr; pub use wstr::WString;
-mod filename; pub use filename::FileName;
+mod wstr; pub use wstr::{WStr, WString};
+mod filename; pub use filename::{FileName, FileNameKind, FileNameRef};
+mod fileref; pub use fileref::FileRef;
+mod token; pub use token::Token;
+mod uuid; pub use uuid::UUID;
+mod cstring; pub use cstring::{CString, WideCString};
+mod lazy; pub use lazy::{LazyArray, LazyArrayIter};
+mod tagged; pub use tagged::Tagged;
 
-use gw2_pf_rs_derive;
 pub(crate) use gw2_pf_rs_derive::Parse;
 
 pub const fn tcc(_str : &[u8; 2]) -> u16 {
-	unsafe{ std::mem::transmute(*_str) }
+	u16::from_le_bytes(*_str)
 }
 
 pub const fn fcc(_str : &[u8; 4]) -> u32 {
-	unsafe{ std::mem::transmute(*_str) }
+	u32::from_le_bytes(*_str)
 }
@@ -0,0 +1,131 @@
+use std::{io::Write, ops::Range, sync::Mutex};
+use crate::parse::Error;
+
+/// Receives events while parsing, attach it with [`crate::parse::Input::with_observer`].
+/// All offsets are absolute positions in the file (see [`crate::parse::Input::offset`]).
+///
+/// Nodes are strictly nested, every `enter` is followed by exactly one `exit` for the same node.
+/// Values behind pointers are entered from inside the node that holds the pointer.
+pub trait ParseObserver : Send + Sync {
+	fn enter(&self, _node : Node, _offset : usize) {}
+	/// `offset` is the end of the inline part of the node, pointed to data is not included.
+	fn exit(&self, _node : Node, _offset : usize, _error : Option<&Error>) {}
+	/// A pointer located at `at` was read, `target` is `None` for null pointers.
+	fn pointer(&self, _at : usize, _target : Option<usize>) {}
+	/// An array of `length` elements is about to be read at `target`.
+	fn array(&self, _element_type : &'static str, _length : usize, _target : Option<usize>) {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Node {
+	/// A struct, the name is the full type name.
+	Type(&'static str),
+	/// A field of a struct or the magic and version variants of chunks and packfiles.
+	Field(&'static str),
+	/// An element of an array.
+	Index(usize),
+}
+
+/// Writes an indented line per event, mostly useful for debugging new formats.
+pub struct TraceObserver<W> {
+	state : Mutex<(W, usize)>,
+}
+
+impl<W : Write + Send> TraceObserver<W> {
+	pub fn new(output : W) -> Self { Self { state: Mutex::new((output, 0)) } }
+
+	fn line(&self, depth_change : isize, line : std::fmt::Arguments) {
+		let Ok(mut state) = self.state.lock() else { return };
+		let (output, depth) = &mut *state;
+		if depth_change < 0 { *depth = depth.saturating_sub(1); }
+		// tracing is best effort, write errors are ignored
+		_ = writeln!(output, "{:indent$}{line}", "", indent = *depth * 2);
+		if depth_change > 0 { *depth += 1; }
+	}
+}
+
+impl TraceObserver<std::io::Stderr> {
+	pub fn stderr() -> Self { Self::new(std::io::stderr()) }
+}
+
+impl<W : Write + Send> ParseObserver for TraceObserver<W> {
+	fn enter(&self, node : Node, offset : usize) {
+		match node {
+			Node::Type(name)  => self.line(1, format_args!("{name} @ {offset:#x}")),
+			Node::Field(name) => self.line(1, format_args!(".{name} @ {offset:#x}")),
+			Node::Index(i)    => self.line(1, format_args!("[{i}] @ {offset:#x}")),
+		}
+	}
+
+	fn exit(&self, _node : Node, offset : usize, error : Option<&Error>) {
+		match error {
+			None    => self.line(-1, format_args!("end @ {offset:#x}")),
+			Some(e) => self.line(-1, format_args!("failed @ {offset:#x}: {e}")),
+		}
+	}
+
+	fn pointer(&self, at : usize, target : Option<usize>) {
+		self.line(0, format_args!("ptr @ {at:#x} -> {}", Target(target)));
+	}
+
+	fn array(&self, element_type : &'static str, length : usize, target : Option<usize>) {
+		self.line(0, format_args!("array of {length} {element_type} -> {}", Target(target)));
+	}
+}
+
+struct Target(Option<usize>);
+
+impl std::fmt::Display for Target {
+	fn fmt(&self, f : &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.0 {
+			Some(offset) => write!(f, "{offset:#x}"),
+			None         => f.write_str("null"),
+		}
+	}
+}
+
+/// Collects the byte ranges read by the parser, attach it with [`crate::parse::Input::with_coverage`].
+/// Ranges are absolute positions in the file, same as the offsets of [`ParseObserver`].
+#[derive(Debug, Default)]
+pub struct Coverage {
+	read : Mutex<Vec<Range<usize>>>,
+}
+
+impl Coverage {
+	pub fn new() -> Self { Self::default() }
+
+	pub fn record(&self, range : Range<usize>) {
+		if range.is_empty() { return }
+		if let Ok(mut read) = self.read.lock() { read.push(range) }
+	}
+
+	/// The sorted and merged ranges that were read.
+	pub fn read_ranges(&self) -> Vec<Range<usize>> {
+		let Ok(read) = self.read.lock() else { return Vec::new() };
+		let mut sorted = read.clone();
+		sorted.sort_unstable_by_key(|r| r.start);
+
+		let mut merged : Vec<Range<usize>> = Vec::with_capacity(sorted.len());
+		for range in sorted {
+			match merged.last_mut() {
+				Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+				_ => merged.push(range),
+			}
+		}
+		merged
+	}
+
+	/// The parts of `within` that were never read.
+	pub fn unread_ranges(&self, within : Range<usize>) -> Vec<Range<usize>> {
+		let mut unread = Vec::new();
+		let mut position = within.start;
+		for range in self.read_ranges() {
+			if range.end <= position { continue }
+			if range.start >= within.end { break }
+			if range.start > position { unread.push(position..range.start) }
+			position = range.end;
+		}
+		if position < within.end { unread.push(position..within.end) }
+		unread
+	}
+}
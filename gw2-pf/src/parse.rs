@@ -1,3 +1,6 @@
+use crate::observe::{Coverage, Node, ParseObserver};
+use std::{borrow::Cow, ops::Range, sync::{atomic::{AtomicUsize, Ordering}, Arc}};
+
 #[derive(Debug)]
 pub enum Error {
 	InvalidFileType{ r#type : &'static str, expected : u32, actual : u32 },
@@ -7,6 +10,37 @@ pub enum Error {
 	UnknownVersion{ r#type : &'static str, actual : u16 },
 	UnknownMagic{ r#type : &'static str, actual : u32 },
 	UnknownMagicOrVersion{ r#type : &'static str, actual_magic : u32, actual_version : u32 }, //TODO(Rennorb) @cleanup
+	UnknownVariant{ r#type : &'static str, actual : u32 },
+	ValueTooLarge { r#type : &'static str, actual : usize, max : usize },
+	InvalidHuffmanTree,
+	InvalidHuffmanCode,
+	InvalidCopySize { code : u16 },
+	InvalidCopyOffset { code : u16 },
+	CopyOutOfBounds { offset : usize, position : usize },
+	/// A size or offset field of a file or chunk header points outside of the data.
+	HeaderFieldOutOfBounds { r#type : &'static str, field : &'static str, value : usize, max : usize },
+	/// A pointer that does not point past itself, or a null pointer where data is required.
+	InvalidPointer { value : u64 },
+	/// Encrypted data was found, but no key or a key of 0 was given. See [`crate::encryption`].
+	MissingDecryptionKey,
+	/// The audio payload can't be converted, see [`crate::export`].
+	UnsupportedAudio { reason : &'static str },
+	/// One of the [`Limits`] of the input was reached.
+	LimitExceeded { limit : Limit, actual : usize, max : usize },
+	/// Wraps an error with the place it occurred at, see [`Error::innermost`].
+	Located {
+		/// From the outermost to the innermost segment.
+		path   : Vec<PathSegment>,
+		/// Absolute position in the file of the innermost value in `path`.
+		offset : usize,
+		source : Box<Error>,
+	},
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+	Field(Cow<'static, str>),
+	Index(usize),
 }
 
 impl Error {
@@ -17,11 +51,58 @@ impl Error {
 	pub fn wrong_magic<T : crate::pf::Magic>(actual : u32) -> Self { Self::InvalidFileType {
 		r#type: std::any::type_name::<T>(), expected: T::MAGIC, actual
 	}} 
+
+	/// Adds a field (or type) name in front of the path of the error. `offset` is where the field starts.
+	pub fn in_field(self, name : impl Into<Cow<'static, str>>, offset : usize) -> Self {
+		self.with_segment(PathSegment::Field(name.into()), offset)
+	}
+
+	/// Adds an array index in front of the path of the error. `offset` is where the element starts.
+	pub fn at_index(self, index : usize, offset : usize) -> Self {
+		self.with_segment(PathSegment::Index(index), offset)
+	}
+
+	fn with_segment(self, segment : PathSegment, offset : usize) -> Self {
+		match self {
+			// the offset is kept from the innermost segment, that is where the error actually happened
+			Error::Located { mut path, offset, source } => {
+				path.insert(0, segment);
+				Error::Located { path, offset, source }
+			},
+			source => Error::Located { path: vec![segment], offset, source: Box::new(source) },
+		}
+	}
+
+	/// The error without location information.
+	pub fn innermost(&self) -> &Error {
+		match self {
+			Error::Located { source, .. } => source,
+			other => other,
+		}
+	}
+
+	/// Strips the location information.
+	pub fn into_innermost(self) -> Error {
+		match self {
+			Error::Located { source, .. } => *source,
+			other => other,
+		}
+	}
 }
 
 impl std::fmt::Display for Error {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
+			Error::Located { path, offset, source } => {
+				for (i, segment) in path.iter().enumerate() {
+					match segment {
+						PathSegment::Field(name) if i == 0 => f.write_str(name)?,
+						PathSegment::Field(name) => f.write_fmt(format_args!(".{name}"))?,
+						PathSegment::Index(index) => f.write_fmt(format_args!("[{index}]"))?,
+					}
+				}
+				f.write_fmt(format_args!(" @ {offset:#x}: {source}"))
+			},
 			Error::DataTooShort { r#type, required, actual } => {
 				f.write_str("Data too short")?;
 				if let Some(r#type) = r#type {
@@ -31,27 +112,50 @@ impl std::fmt::Display for Error {
 			},
 			Error::InvalidFileType { r#type, expected, actual } => {
 				f.write_fmt(format_args!("Unexpected file type for {}: expected: {:x?} ({}), actual: {:x?} ({})", r#type,
-					&expected.to_le_bytes(), unsafe { std::str::from_utf8_unchecked(std::slice::from_raw_parts(std::ptr::from_ref(expected).cast(), 4)) },
-					&actual.to_le_bytes(), unsafe { std::str::from_utf8_unchecked(std::slice::from_raw_parts(std::ptr::from_ref(actual).cast(), 4)) }
+					&expected.to_le_bytes(), String::from_utf8_lossy(&expected.to_le_bytes()),
+					&actual.to_le_bytes(), String::from_utf8_lossy(&actual.to_le_bytes())
 				))
 			},
 			Error::UnknownMagic { r#type, actual } => {
 				f.write_fmt(format_args!("Unexpected magic bytes for {}: {:x?} ({})", r#type,
-					&actual.to_le_bytes(), unsafe { std::str::from_utf8_unchecked(std::slice::from_raw_parts(std::ptr::from_ref(actual).cast(), 4)) }
+					&actual.to_le_bytes(), String::from_utf8_lossy(&actual.to_le_bytes())
 				))
 			},
 			Error::UnknownMagicOrVersion { r#type, actual_magic, actual_version } => {
 				f.write_fmt(format_args!("Unexpected magic bytes or version for {}: actual magic: {:x?} ({}), version: {}", r#type,
-					&actual_magic.to_le_bytes(), unsafe { std::str::from_utf8_unchecked(std::slice::from_raw_parts(std::ptr::from_ref(actual_magic).cast(), 4)) },
+					&actual_magic.to_le_bytes(), String::from_utf8_lossy(&actual_magic.to_le_bytes()),
 					actual_version
 				))
 			},
+			Error::UnknownVariant { r#type, actual } => {
+				f.write_fmt(format_args!("Unknown variant index for {}: {actual}", r#type))
+			},
+			Error::ValueTooLarge { r#type, actual, max } => {
+				f.write_fmt(format_args!("Value too large for {}: max: {max}, actual: {actual}", r#type))
+			},
+			Error::InvalidHuffmanTree => f.write_str("Invalid huffman tree in compressed data"),
+			Error::InvalidHuffmanCode => f.write_str("Invalid huffman code in compressed data"),
+			Error::InvalidCopySize { code } => f.write_fmt(format_args!("Invalid copy size code in compressed data: {code}")),
+			Error::InvalidCopyOffset { code } => f.write_fmt(format_args!("Invalid copy offset code in compressed data: {code}")),
+			Error::CopyOutOfBounds { offset, position } => f.write_fmt(format_args!("Copy offset in compressed data out of bounds: offset: {offset}, position: {position}")),
+			Error::LimitExceeded { limit, actual, max } => f.write_fmt(format_args!("Parse limit exceeded: {limit:?}: max: {max}, actual: {actual}")),
+			Error::UnsupportedAudio { reason } => f.write_fmt(format_args!("Unsupported audio: {reason}")),
+			Error::MissingDecryptionKey => f.write_str("The data is encrypted, but no decryption key was given"),
+			Error::InvalidPointer { value } => f.write_fmt(format_args!("Invalid pointer value: {value}")),
+			Error::HeaderFieldOutOfBounds { r#type, field, value, max } => f.write_fmt(format_args!("Header field {field} of {} out of bounds: max: {max}, actual: {value}", r#type)),
 			_ => f.write_fmt(format_args!("{:?}", self))
 		}
 	}
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::Located { source, .. } => Some(source.as_ref()),
+			_ => None,
+		}
+	}
+}
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -70,6 +174,11 @@ impl BinarySize {
 		ptrs: self.ptrs + rhs.ptrs,
 		is_dynamic: self.is_dynamic | rhs.is_dynamic,
 	}}
+	pub const fn mul(&self, count : usize) -> Self { Self {
+		fixed_bytes: self.fixed_bytes * count,
+		ptrs: self.ptrs * count,
+		is_dynamic: self.is_dynamic,
+	}}
 
 	pub const fn actual_size(&self, uses_64_bit_ptrs : bool) -> Option<usize> {
 		if self.is_dynamic { return None }
@@ -82,28 +191,188 @@ pub trait Parse<'inp> : Sized {
 	fn parse(input : &mut Input<'inp>) -> Result<Self>;
 }
 
+/// Bounds for parsing untrusted data, see [`Input::with_limits`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+	/// Bytes that all arrays parsed from one input, including the inputs derived from it, may allocate in total.
+	pub max_total_allocation : usize,
+	/// Elements a single array may have. Borrowed byte slices don't allocate and are not affected.
+	pub max_array_length     : usize,
+	/// Pointers that may be followed in a chain.
+	pub max_depth            : usize,
+}
+
+impl Limits {
+	pub const UNLIMITED : Self = Self { max_total_allocation: usize::MAX, max_array_length: usize::MAX, max_depth: usize::MAX };
+}
+
+impl Default for Limits {
+	fn default() -> Self { Self::UNLIMITED }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+	TotalAllocation,
+	ArrayLength,
+	Depth,
+}
+
 #[derive(Clone)]
 pub struct Input<'inp> {
 	pub remaining : &'inp [u8],
 	pub is_64_bit : bool,
+	/// Address of the start of the file, to compute absolute offsets.
+	origin        : usize,
+	limits        : Limits,
+	depth         : usize,
+	/// Shared by all inputs derived from the same root, so the allocations of nested values add up.
+	allocated     : Arc<AtomicUsize>,
+	observer      : Option<Arc<dyn ParseObserver>>,
+	coverage      : Option<Arc<Coverage>>,
+}
+
+impl std::fmt::Debug for Input<'_> {
+	fn fmt(&self, f : &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("Input")
+			.field("remaining", &self.remaining.len())
+			.field("is_64_bit", &self.is_64_bit)
+			.field("offset", &self.offset())
+			.field("limits", &self.limits)
+			.field("depth", &self.depth)
+			.field("allocated", &self.allocated.load(Ordering::Relaxed))
+			.field("observer", &self.observer.is_some())
+			.field("coverage", &self.coverage.is_some())
+			.finish()
+	}
 }
 
-impl Input<'_> {
+impl<'inp> Input<'inp> {
+	pub fn new(remaining : &'inp [u8], is_64_bit : bool) -> Self {
+		Self::with_limits(remaining, is_64_bit, Limits::UNLIMITED)
+	}
+
+	pub fn with_limits(remaining : &'inp [u8], is_64_bit : bool, limits : Limits) -> Self {
+		Self { remaining, is_64_bit, origin: remaining.as_ptr() as usize, limits, depth: 0, allocated: Arc::new(AtomicUsize::new(0)), observer: None, coverage: None }
+	}
+
+	/// Reports everything parsed from this input and the inputs derived from it to `observer`.
+	pub fn with_observer(mut self, observer : Arc<dyn ParseObserver>) -> Self {
+		self.observer = Some(observer);
+		self
+	}
+
+	pub fn observer(&self) -> Option<&Arc<dyn ParseObserver>> { self.observer.as_ref() }
+
+	/// Records all bytes read from this input and the inputs derived from it in `coverage`.
+	pub fn with_coverage(mut self, coverage : Arc<Coverage>) -> Self {
+		self.coverage = Some(coverage);
+		self
+	}
+
+	pub fn coverage(&self) -> Option<&Arc<Coverage>> { self.coverage.as_ref() }
+
+	/// Marks the next `length` bytes as read if coverage is tracked.
+	/// Only needed for parse implementations that look at `remaining` directly instead of going through other [`Parse`] impls.
+	pub fn record_read(&self, length : usize) {
+		if let Some(coverage) = &self.coverage {
+			let offset = self.offset();
+			coverage.record(offset..offset.saturating_add(length));
+		}
+	}
+
+	/// Position of the remaining data relative to the data the root input was created with.
+	pub fn offset(&self) -> usize { (self.remaining.as_ptr() as usize).wrapping_sub(self.origin) }
+
+	pub fn limits(&self) -> Limits { self.limits }
+
+	/// Creates an input for `remaining` that shares the limits and allocation budget of this one.
+	/// Offsets are still computed relative to the original data, so `remaining` should be a part of it.
+	pub fn with_data<'o>(&self, remaining : &'o [u8]) -> Input<'o> {
+		Input { remaining, is_64_bit: self.is_64_bit, origin: self.origin, limits: self.limits, depth: self.depth, allocated: self.allocated.clone(), observer: self.observer.clone(), coverage: self.coverage.clone() }
+	}
+
+	/// Parses a struct of type `r#type`.
+	pub fn parse_type<T>(&mut self, r#type : &'static str, parse : impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+		self.observed(Node::Type(r#type), parse)
+	}
+
+	/// Parses the field `name`, errors get the field added to their path.
+	pub fn parse_field<T>(&mut self, name : &'static str, parse : impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+		let offset = self.offset();
+		self.observed(Node::Field(name), parse).map_err(|e| e.in_field(name, offset))
+	}
+
+	/// Parses the array element at `index`, errors get the index added to their path.
+	pub fn parse_element<T>(&mut self, index : usize, parse : impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+		let offset = self.offset();
+		self.observed(Node::Index(index), parse).map_err(|e| e.at_index(index, offset))
+	}
+
+	fn observed<T>(&mut self, node : Node, parse : impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+		let Some(observer) = self.observer.clone() else { return parse(self) };
+		observer.enter(node, self.offset());
+		let result = parse(self);
+		observer.exit(node, self.offset(), result.as_ref().err());
+		result
+	}
+
+	pub(crate) fn observe_array<T>(&self, length : usize, offset : Option<usize>) {
+		if let Some(observer) = &self.observer {
+			observer.array(std::any::type_name::<T>(), length, offset.map(|o| self.offset().wrapping_add(o)));
+		}
+	}
+
 	pub fn clone_with_offset(&self, offset : usize) -> Result<Self> {
-		if offset > self.remaining.len() { Err(Error::DataTooShort{ r#type: None, required: offset, actual: self.remaining.len() }) }
-		else { Ok(Self { remaining: &self.remaining[offset..], is_64_bit: self.is_64_bit }) }
+		if offset > self.remaining.len() { return Err(Error::DataTooShort{ r#type: None, required: offset, actual: self.remaining.len() }) }
+		let depth = self.depth + 1;
+		if depth > self.limits.max_depth { return Err(Error::LimitExceeded { limit: Limit::Depth, actual: depth, max: self.limits.max_depth }) }
+		Ok(Self { remaining: &self.remaining[offset..], depth, ..self.clone() })
 	}
 
-	pub fn eat_offset(&mut self) -> Result<usize>  {
-		if self.is_64_bit {
-			let v = u64::parse(self)? as usize;
-			Ok(if v != 0 { v - 8 } else { 0 })
+	/// Checks `length` against the limits and charges the memory for that many elements of `T` to the allocation budget.
+	/// Has to be called before allocating an array of a length read from the input.
+	pub fn reserve_array<T>(&self, length : usize) -> Result<()> {
+		if length > self.limits.max_array_length {
+			return Err(Error::LimitExceeded { limit: Limit::ArrayLength, actual: length, max: self.limits.max_array_length })
 		}
-		else {
-			let v = u32::parse(self)? as usize;
-			Ok(if v != 0 { v - 4 } else { 0 })
+
+		let size = length.saturating_mul(std::mem::size_of::<T>());
+		let max = self.limits.max_total_allocation;
+		let previous = self.allocated.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |allocated| {
+			let allocated = allocated.saturating_add(size);
+			(allocated <= max).then_some(allocated)
+		});
+		match previous {
+			Ok(_) => Ok(()),
+			Err(allocated) => Err(Error::LimitExceeded { limit: Limit::TotalAllocation, actual: allocated.saturating_add(size), max }),
 		}
 	}
+
+	/// Reads a pointer and returns the offset of its target relative to the end of the pointer, `None` for null pointers.
+	pub fn eat_offset(&mut self) -> Result<Option<usize>>  {
+		let at = self.offset();
+		let (value, size) = if self.is_64_bit { (u64::parse(self)?, 8) } else { (u32::parse(self)? as u64, 4) };
+		let offset = match value {
+			0 => None,
+			// pointers are relative to their own position, anything smaller than the pointer itself would point backwards into it
+			value if value < size => return Err(Error::InvalidPointer { value }),
+			value => Some(usize::try_from(value - size).map_err(|_| Error::InvalidPointer { value })?),
+		};
+		if let Some(observer) = &self.observer { observer.pointer(at, offset.map(|o| self.offset().wrapping_add(o))); }
+		Ok(offset)
+	}
+
+	/// Follows the pointer of an array with a non zero element count, which must not be null.
+	pub fn clone_with_array_offset(&self, offset : Option<usize>) -> Result<Self> {
+		self.clone_with_offset(offset.ok_or(Error::InvalidPointer { value: 0 })?)
+	}
+}
+
+/// Capacity to reserve for `length` elements of `T` that are about to be read from `input`.
+/// The length comes from the file, so it is only trusted as far as the remaining data could actually hold that many elements.
+fn element_capacity<'inp, T : Parse<'inp>>(length : usize, input : &Input<'inp>) -> usize {
+	let element_size = T::BINARY_SIZE.actual_size(input.is_64_bit).unwrap_or(1).max(1);
+	std::cmp::min(length, input.remaining.len() / element_size)
 }
 
 impl<'inp> Parse<'inp> for () {
@@ -121,6 +390,7 @@ macro_rules! impl_le_bit_prase {
 				const BINARY_SIZE : usize = std::mem::size_of::<$type>();
 				if input.remaining.len() < BINARY_SIZE { return Err(Error::to_short::<$type>(input.remaining.len())) }
 				let v = <$type>::from_le_bytes(input.remaining[..BINARY_SIZE].try_into().unwrap());
+				input.record_read(BINARY_SIZE);
 				input.remaining = &input.remaining[BINARY_SIZE..];
 				Ok(v)
 			}
@@ -135,13 +405,15 @@ impl_le_bit_prase! {
 }
 
 //NOTE(Rennorb): Currently we always treat options as pointers, this might need to change.
+// Arrays of pointers are `Vec<Option<T>>` because of this.
 impl<'inp, T : Parse<'inp>> Parse<'inp> for Option<T> {
 	const BINARY_SIZE : BinarySize = BinarySize::ptrs(1);
 	fn parse(input : &mut Input<'inp>) -> Result<Self> {
 		let offset = input.eat_offset()?;
-		#[cfg(feature = "debug-parsing")] eprintln!(" ptr: offset: {offset:x}");
-		if offset == 0 { Ok(None) }
-		else { T::parse(&mut input.clone_with_offset(offset)?).map(Some) }
+		match offset {
+			None => Ok(None),
+			Some(offset) => T::parse(&mut input.clone_with_offset(offset)?).map(Some),
+		}
 	}
 }
 
@@ -149,46 +421,85 @@ impl<'inp, T : Parse<'inp>> Parse<'inp> for Vec<T> {
 	const BINARY_SIZE : BinarySize = BinarySize{ fixed_bytes: 4, ptrs: 1, is_dynamic: false };
 	fn parse(input : &mut Input<'inp>) -> Result<Self> {
 		let length = u32::parse(input)? as usize;
-		let offset = input.eat_offset()?;
-		#[cfg(feature = "debug-parsing")] eprintln!(" vec: offset: {offset:x}, len: {length}");
-		match length {
-			0 => Ok(vec![]),
-			//todo length check
-			mut length => {
-				let vec_input = &mut input.clone_with_offset(offset)?;
-
-				let mut vec = Vec::with_capacity(length);
-				while length > 0 {
-					vec.push(T::parse(vec_input)?);
-					length -= 1;
+		parse_vec_elements(length, input)
+	}
+}
+
+/// Binary size of a `#[small]` array, see [`parse_small_vec`].
+pub const SMALL_VEC_BINARY_SIZE : BinarySize = BinarySize{ fixed_bytes: 2, ptrs: 1, is_dynamic: false };
+
+/// Same layout as `Vec<T>`, but with a 16 bit length. Used for fields marked with `#[small]`.
+pub fn parse_small_vec<'inp, T : Parse<'inp>>(input : &mut Input<'inp>) -> Result<Vec<T>> {
+	let length = u16::parse(input)? as usize;
+	parse_vec_elements(length, input)
+}
+
+/// The pointer and elements of an array of which the length was already read.
+fn parse_vec_elements<'inp, T : Parse<'inp>>(length : usize, input : &mut Input<'inp>) -> Result<Vec<T>> {
+	let offset = input.eat_offset()?;
+	input.observe_array::<T>(length, offset);
+	match length {
+		0 => Ok(vec![]),
+		length => {
+			let vec_input = &mut input.clone_with_array_offset(offset)?;
+
+			if let Some(element_size) = T::BINARY_SIZE.actual_size(input.is_64_bit) {
+				let required = length.saturating_mul(element_size);
+				if vec_input.remaining.len() < required {
+					return Err(Error::DataTooShort { r#type: Some(std::any::type_name::<Vec<T>>()), required, actual: vec_input.remaining.len() })
 				}
-	
-				Ok(vec)
 			}
+
+			input.reserve_array::<T>(length)?;
+			let mut vec = Vec::with_capacity(element_capacity::<T>(length, vec_input));
+			for i in 0..length {
+				vec.push(vec_input.parse_element(i, T::parse)?);
+			}
+
+			Ok(vec)
+		}
+	}
+}
+
+/// Inline array, the elements are stored directly in place without a length or pointer.
+impl<'inp, T : Parse<'inp>, const N : usize> Parse<'inp> for [T; N] {
+	const BINARY_SIZE : BinarySize = T::BINARY_SIZE.mul(N);
+	fn parse(input : &mut Input<'inp>) -> Result<Self> {
+		if let Some(required) = Self::BINARY_SIZE.actual_size(input.is_64_bit) {
+			if input.remaining.len() < required { return Err(Error::DataTooShort { r#type: Some(std::any::type_name::<Self>()), required, actual: input.remaining.len() }) }
+		}
+
+		let mut elements = Vec::with_capacity(N);
+		for i in 0..N {
+			elements.push(input.parse_element(i, T::parse)?);
 		}
+		Ok(elements.try_into().unwrap_or_else(|_| unreachable!("exactly N elements were parsed")))
 	}
 }
 
 pub fn parse_null_terminated_vec<'inp, T : Parse<'inp>>(input : &mut Input<'inp>) -> Result<Vec<T>> {
 	let length = u32::parse(input)? as usize;
 	let offset = input.eat_offset()?;
-	#[cfg(feature = "debug-parsing")] eprintln!(" null term vec: offset: {offset:x}, len: {length}");
+	input.observe_array::<T>(length, offset);
 	match length { 
 		0 => Ok(vec![]),
-		//todo length check
 		mut length => {
-			let vec_input = &mut input.clone_with_offset(offset)?;
+			let vec_input = &mut input.clone_with_array_offset(offset)?;
 
-			let mut vec = Vec::with_capacity(length);
+			input.reserve_array::<T>(length)?;
+			let mut vec = Vec::with_capacity(element_capacity::<T>(length, vec_input));
 			while length > 0 {
 				//todo test during macro expansion
 				if let Some(el_size) = T::BINARY_SIZE.actual_size(input.is_64_bit) {
 					if vec_input.remaining.len() < el_size { return Err(Error::to_short::<T>(vec_input.remaining.len())); }
 
-					if vec_input.remaining[..el_size].iter().all(|b| *b == 0) { break }
+					if vec_input.remaining[..el_size].iter().all(|b| *b == 0) {
+						vec_input.record_read(el_size);
+						break
+					}
 				}
 
-				vec.push(T::parse(vec_input)?);
+				vec.push(vec_input.parse_element(vec.len(), T::parse)?);
 				length -= 1;
 			}
 
@@ -202,13 +513,16 @@ impl<'inp> Parse<'inp> for &'inp [u8] {
 	fn parse(input : &mut Input<'inp>) -> Result<Self> {
 		let length = u32::parse(input)? as usize;
 		let offset = input.eat_offset()?;
-		#[cfg(feature = "debug-parsing")] eprintln!(" vec: offset: {offset:x}, len: {length}");
+		input.observe_array::<u8>(length, offset);
 		match length { 
 			0 => Ok(&[]),
-			length if input.remaining.len() < offset + length => Err(Error::DataTooShort {
-				r#type: Some(std::any::type_name::<Self>()), required: offset + length, actual: input.remaining.len(),
-			}),
-			length => Ok(&input.remaining[offset..][..length]),
+			length => {
+				let data_input = input.clone_with_array_offset(offset)?;
+				let data = data_input.remaining;
+				if data.len() < length { return Err(Error::DataTooShort { r#type: Some(std::any::type_name::<Self>()), required: length, actual: data.len() }) }
+				data_input.record_read(length);
+				Ok(&data[..length])
+			},
 		}
 	}
 }
@@ -218,28 +532,116 @@ pub trait ParseVersioned<'inp> : Sized {
 	fn parse(version : u16, input : &mut Input<'inp>) -> Result<Self::Output>;
 }
 
+/// One alternative of a tagged union, implemented by enums deriving `Parse` with `#[variant]`. See [`crate::Tagged`].
+pub trait ParseVariant<'inp> : Sized {
+	fn parse(index : u32, input : &mut Input<'inp>) -> Result<Self>;
+}
+
 pub trait ParseMagicVariant<'inp> : Sized {
 	fn parse(magic : u32, version : u16, input : &mut Input<'inp>) -> Result<Self>;
 }
 
-pub struct ChunkIter<'inp, V : ParseMagicVariant<'inp>> {
+/// A chunk of a packfile that has not been interpreted yet.
+#[derive(Debug, Clone)]
+pub struct RawChunk<'inp> {
+	pub header    : crate::pf::ChunkHeader,
+	/// The chunk descriptor, `header.descriptor_offset` bytes starting after the chunk header.
+	pub data      : &'inp [u8],
+	pub is_64_bit : bool,
+	/// Carries the limits of the file the chunk was read from.
+	input         : Input<'inp>,
+}
+
+impl<'inp> RawChunk<'inp> {
+	pub fn magic(&self) -> u32 { self.header.magic }
+	pub fn version(&self) -> u16 { self.header.version }
+
+	pub fn input(&self) -> Input<'inp> { self.input.clone() }
+
+	pub fn parse<V : ParseMagicVariant<'inp>>(&self) -> Result<V> {
+		V::parse(self.header.magic, self.header.version, &mut self.input())
+	}
+
+	/// Position of `data` in the file.
+	pub fn data_range(&self) -> Range<usize> {
+		let start = self.input.offset();
+		start..start + self.data.len()
+	}
+
+	/// Parses the chunk and also returns the ranges of `data` that the parser never read.
+	pub fn parse_with_coverage<V : ParseMagicVariant<'inp>>(&self) -> Result<(V, Vec<Range<usize>>)> {
+		let coverage = Arc::new(Coverage::new());
+		let value = V::parse(self.header.magic, self.header.version, &mut self.input().with_coverage(coverage.clone()))?;
+		Ok((value, coverage.unread_ranges(self.data_range())))
+	}
+}
+
+pub struct RawChunkIter<'inp> {
 	pub input : Input<'inp>,
-	pub _p : std::marker::PhantomData<V>
 }
 
-impl<'inp, V : ParseMagicVariant<'inp>> Iterator for ChunkIter<'inp, V> {
-	type Item = Result<V>;
+impl<'inp> Iterator for RawChunkIter<'inp> {
+	type Item = Result<RawChunk<'inp>>;
 
 	fn next(&mut self) -> Option<Self::Item> {
+		//NOTE(Rennorb): Some files end in padding that is shorter than a chunk header, it is not an error.
 		if self.input.remaining.len() < std::mem::size_of::<crate::pf::ChunkHeader>() { return None }
 
-		let chunk_header = unsafe{ self.input.remaining.as_ptr().cast::<crate::pf::ChunkHeader>().as_ref().unwrap() };
-		let chunk_data = &self.input.remaining[chunk_header.chunk_header_size as usize..][..chunk_header.descriptor_offset as usize];
-		let chunk_input = &mut Input { remaining: chunk_data, is_64_bit: self.input.is_64_bit };
+		let result = self.next_chunk();
+		// don't try to continue after a broken header, the position of the next chunk is unknown
+		if result.is_err() { self.input.remaining = &[]; }
+		Some(result)
+	}
+}
+
+impl<'inp> RawChunkIter<'inp> {
+	/// Reports the parsing of all following chunks to `observer`.
+	pub fn with_observer(self, observer : Arc<dyn ParseObserver>) -> Self {
+		Self { input: self.input.with_observer(observer) }
+	}
+
+	fn next_chunk(&mut self) -> Result<RawChunk<'inp>> {
+		use crate::pf::ChunkHeader;
+
+		let remaining = self.input.remaining;
+		//NOTE(Rennorb): Header errors are reported as is, the fields of the headers are not part of any path.
+		let header = ChunkHeader::parse(&mut self.input.clone()).map_err(Error::into_innermost)?;
+
+		let out_of_bounds = |field, value, max| Error::HeaderFieldOutOfBounds { r#type: std::any::type_name::<ChunkHeader>(), field, value, max };
 
-		let next_offset = 8 + chunk_header.next_chunk_offset as usize;  // +8 = after "offset" field
-		if next_offset <= self.input.remaining.len() { self.input.remaining = &self.input.remaining[next_offset..]; }
-		
-		Some(V::parse(chunk_header.magic, chunk_header.version, chunk_input))
+		let header_size = header.chunk_header_size as usize;
+		if header_size < std::mem::size_of::<ChunkHeader>() || header_size > remaining.len() {
+			return Err(out_of_bounds("chunk_header_size", header_size, remaining.len()))
+		}
+		let data = &remaining[header_size..];
+		let descriptor_size = header.descriptor_offset as usize;
+		if descriptor_size > data.len() { return Err(out_of_bounds("descriptor_offset", descriptor_size, data.len())) }
+
+		let next_offset = 8 + header.next_chunk_offset as usize; // +8 = after "offset" field
+		if next_offset > remaining.len() { return Err(out_of_bounds("next_chunk_offset", header.next_chunk_offset as usize, remaining.len() - 8)) }
+
+		self.input.remaining = &remaining[next_offset..];
+		let data = &data[..descriptor_size];
+		Ok(RawChunk { header, data, is_64_bit: self.input.is_64_bit, input: self.input.with_data(data) })
+	}
+}
+
+pub struct ChunkIter<'inp, V : ParseMagicVariant<'inp>> {
+	pub chunks : RawChunkIter<'inp>,
+	pub _p : std::marker::PhantomData<V>
+}
+
+impl<'inp, V : ParseMagicVariant<'inp>> ChunkIter<'inp, V> {
+	/// Reports the parsing of all following chunks to `observer`.
+	pub fn with_observer(self, observer : Arc<dyn ParseObserver>) -> Self {
+		Self { chunks: self.chunks.with_observer(observer), ..self }
+	}
+}
+
+impl<'inp, V : ParseMagicVariant<'inp>> Iterator for ChunkIter<'inp, V> {
+	type Item = Result<V>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.chunks.next().map(|chunk| chunk?.parse())
 	}
 }
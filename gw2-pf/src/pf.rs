@@ -1,23 +1,100 @@
-use crate::parse::{ChunkIter, Error, Input, ParseMagicVariant, Result};
+use crate::{parse::{ChunkIter, Error, Input, Limits, Parse, ParseMagicVariant, RawChunkIter, Result}, serialize::{Output, Serialize, SerializeMagicVariant}};
 
 pub struct PackFileReader<'inp, F : Magic + ParseMagicVariant<'inp>> {
 	_p : std::marker::PhantomData<&'inp F>,
 }
 
 impl<'inp, F : Magic + ParseMagicVariant<'inp>> PackFileReader<'inp, F> {
-	pub fn from_bytes(bytes : &'inp [u8]) -> Result<ChunkIter<F>> {
-		if bytes.len() < std::mem::size_of::<PFHeader>() { return Err(Error::to_short::<PFHeader>(bytes.len())) }
+	pub fn from_bytes(bytes : &'inp [u8]) -> Result<ChunkIter<'inp, F>> {
+		Self::from_bytes_with_limits(bytes, Limits::UNLIMITED)
+	}
 
-		let header = unsafe{ bytes.as_ptr().cast::<PFHeader>().as_ref().unwrap() };
-		if header.magic != PF_MAGIC { return Err(Error::InvalidFileType { r#type: std::any::type_name::<PFHeader>(), expected: PF_MAGIC as u32, actual: header.magic as u32 }); }
+	/// Parses untrusted data, `limits` apply to the whole file.
+	pub fn from_bytes_with_limits(bytes : &'inp [u8], limits : Limits) -> Result<ChunkIter<'inp, F>> {
+		let RawPackFile { header, chunks } = RawPackFile::from_bytes_with_limits(bytes, limits)?;
 		if header.file_type != F::MAGIC { return Err(Error::wrong_magic::<F>(header.file_type)) }
 
-		let input = Input{ remaining: &bytes[header.header_size as usize..], is_64_bit: header.flags & PF_FLAG_HAS_64BIT_PTRS != 0 };
+		Ok(ChunkIter{ chunks, _p : std::marker::PhantomData })
+	}
+}
 
-		Ok(ChunkIter{ input, _p : std::marker::PhantomData })
+/// Format agnostic view of a packfile, usable for files without a modeled format.
+pub struct RawPackFile<'inp> {
+	pub header : PFHeader,
+	pub chunks : RawChunkIter<'inp>,
+}
+
+impl<'inp> RawPackFile<'inp> {
+	pub fn from_bytes(bytes : &'inp [u8]) -> Result<Self> {
+		Self::from_bytes_with_limits(bytes, Limits::UNLIMITED)
+	}
+
+	pub fn from_bytes_with_limits(bytes : &'inp [u8], limits : Limits) -> Result<Self> {
+		let header = PFHeader::parse(&mut Input::new(bytes, false)).map_err(Error::into_innermost)?;
+		if header.magic != PF_MAGIC { return Err(Error::InvalidFileType { r#type: std::any::type_name::<PFHeader>(), expected: PF_MAGIC as u32, actual: header.magic as u32 }); }
+
+		let header_size = header.header_size as usize;
+		if header_size < std::mem::size_of::<PFHeader>() || header_size > bytes.len() {
+			return Err(Error::HeaderFieldOutOfBounds { r#type: std::any::type_name::<PFHeader>(), field: "header_size", value: header_size, max: bytes.len() })
+		}
+
+		// created from the whole file so offsets are absolute
+		let mut input = Input::with_limits(bytes, header.is_64_bit(), limits);
+		input.remaining = &bytes[header_size..];
+
+		Ok(Self { header, chunks: RawChunkIter { input } })
 	}
 }
 
+pub struct PackFileWriter<F : Magic + SerializeMagicVariant> {
+	buffer    : Vec<u8>,
+	is_64_bit : bool,
+	_p        : std::marker::PhantomData<F>,
+}
+
+impl<F : Magic + SerializeMagicVariant> PackFileWriter<F> {
+	/// Pointer size of the chunks is taken from [`PF_FLAG_HAS_64BIT_PTRS`] in `flags`.
+	pub fn new(flags : u16) -> Self {
+		let header = PFHeader {
+			magic      : PF_MAGIC,
+			flags,
+			_reserved  : 0,
+			header_size: std::mem::size_of::<PFHeader>() as u16,
+			file_type  : F::MAGIC,
+		};
+		let mut output = Output::new(false);
+		header.serialize(&mut output).unwrap(); // fixed size, cannot fail
+		Self { buffer: output.buffer, is_64_bit: flags & PF_FLAG_HAS_64BIT_PTRS != 0, _p: std::marker::PhantomData }
+	}
+
+	pub fn write_chunk(&mut self, chunk : &F) -> Result<()> {
+		let mut output = Output::new(self.is_64_bit);
+		chunk.serialize(&mut output)?;
+		let data = output.finish()?;
+
+		let header_size = std::mem::size_of::<ChunkHeader>();
+		let max = u32::MAX as usize - header_size;
+		if data.len() > max { return Err(Error::ValueTooLarge { r#type: std::any::type_name::<F>(), actual: data.len(), max }) }
+
+		let header = ChunkHeader {
+			magic            : chunk.magic(),
+			next_chunk_offset: (header_size - 8 + data.len()) as u32, // -8 = relative to the end of this field
+			version          : chunk.version(),
+			chunk_header_size: header_size as u16,
+			descriptor_offset: data.len() as u32,
+		};
+		let mut output = Output::new(self.is_64_bit);
+		header.serialize(&mut output)?;
+		self.buffer.extend_from_slice(&output.buffer);
+		self.buffer.extend_from_slice(&data);
+
+		Ok(())
+	}
+
+	pub fn finish(self) -> Vec<u8> { self.buffer }
+}
+
+#[derive(Debug, Clone, crate::Parse)]
 #[repr(C)]
 pub struct PFHeader {
 	pub magic       : u16,
@@ -27,6 +104,10 @@ pub struct PFHeader {
 	pub file_type   : u32,
 }
 
+impl PFHeader {
+	pub fn is_64_bit(&self) -> bool { self.flags & PF_FLAG_HAS_64BIT_PTRS != 0 }
+}
+
 pub const PF_FLAG_HAS_64BIT_PTRS : u16 = 1 << 2;
 pub const PF_MAGIC : u16 = crate::tcc(b"PF");
 
@@ -35,6 +116,7 @@ pub trait Magic : Sized {
 	const MAGIC : u32;
 }
 
+#[derive(Debug, Clone, crate::Parse)]
 #[repr(C)]
 pub struct ChunkHeader {
 	pub magic             : u32,
@@ -0,0 +1,197 @@
+use crate::parse::{Error, Result};
+
+/// Counterpart to [`crate::parse::Parse`].
+/// Writes the fixed part of a value into `output` and hands out-of-line data (everything [`crate::parse::Input::eat_offset`] would follow) to [`Output::write_pointer`].
+pub trait Serialize {
+	fn serialize(&self, output : &mut Output) -> Result<()>;
+}
+
+/// Serialization target for one chunk.
+///
+/// Pointed-to data is collected into child outputs and only laid out once [`Output::finish`] is called.
+/// Children are placed breadth first after the data of their parent, so every offset points forward the same way the parser expects it.
+pub struct Output {
+	pub buffer    : Vec<u8>,
+	pub is_64_bit : bool,
+	pointers      : Vec<(usize, Output)>,
+}
+
+impl Output {
+	pub fn new(is_64_bit : bool) -> Self {
+		Self { buffer: Vec::new(), is_64_bit, pointers: Vec::new() }
+	}
+
+	pub fn write_bytes(&mut self, bytes : &[u8]) {
+		self.buffer.extend_from_slice(bytes);
+	}
+
+	pub fn write_length(&mut self, length : usize) -> Result<()> {
+		match u32::try_from(length) {
+			Ok(length) => { self.write_bytes(&length.to_le_bytes()); Ok(()) },
+			Err(_) => Err(Error::ValueTooLarge { r#type: "array length", actual: length, max: u32::MAX as usize }),
+		}
+	}
+
+	pub fn write_null_pointer(&mut self) {
+		let size = if self.is_64_bit { 8 } else { 4 };
+		self.buffer.resize(self.buffer.len() + size, 0);
+	}
+
+	/// Writes a placeholder pointer and serializes the pointed-to data through `write` into a fresh child output.
+	pub fn write_pointer(&mut self, write : impl FnOnce(&mut Output) -> Result<()>) -> Result<()> {
+		let mut child = Output::new(self.is_64_bit);
+		write(&mut child)?;
+		self.pointers.push((self.buffer.len(), child));
+		self.write_null_pointer();
+		Ok(())
+	}
+
+	/// Lays out all pointed-to data and patches the pointers to match.
+	pub fn finish(self) -> Result<Vec<u8>> {
+		let Output { buffer: mut result, is_64_bit, pointers } = self;
+
+		let mut queue = std::collections::VecDeque::new();
+		queue.push_back((0, pointers));
+		while let Some((base, pointers)) = queue.pop_front() {
+			for (position, child) in pointers {
+				let pointer_position = base + position;
+				let target = result.len();
+				// offsets are relative to the position of the pointer itself, see `Input::eat_offset`
+				let offset = target - pointer_position;
+				if is_64_bit {
+					result[pointer_position..][..8].copy_from_slice(&(offset as u64).to_le_bytes());
+				}
+				else {
+					let offset = u32::try_from(offset).map_err(|_| Error::ValueTooLarge { r#type: "pointer offset", actual: offset, max: u32::MAX as usize })?;
+					result[pointer_position..][..4].copy_from_slice(&offset.to_le_bytes());
+				}
+
+				result.extend_from_slice(&child.buffer);
+				queue.push_back((target, child.pointers));
+			}
+		}
+
+		Ok(result)
+	}
+}
+
+impl Serialize for () {
+	fn serialize(&self, _output : &mut Output) -> Result<()> {
+		Ok(())
+	}
+}
+
+macro_rules! impl_le_bit_serialize {
+	($($type:ty),*) => {$(
+		impl Serialize for $type {
+			fn serialize(&self, output : &mut Output) -> Result<()> {
+				output.write_bytes(&self.to_le_bytes());
+				Ok(())
+			}
+		}
+	)*}
+}
+
+impl_le_bit_serialize! {
+	u8, u16, u32, u64,
+	i8, i16, i32, i64,
+	         f32, f64
+}
+
+impl<T : Serialize> Serialize for Option<T> {
+	fn serialize(&self, output : &mut Output) -> Result<()> {
+		match self {
+			Some(value) => output.write_pointer(|output| value.serialize(output)),
+			None => { output.write_null_pointer(); Ok(()) },
+		}
+	}
+}
+
+impl<T : Serialize> Serialize for Vec<T> {
+	fn serialize(&self, output : &mut Output) -> Result<()> {
+		output.write_length(self.len())?;
+		if self.is_empty() { output.write_null_pointer(); return Ok(()) }
+
+		output.write_pointer(|output| {
+			for element in self {
+				element.serialize(output)?;
+			}
+			Ok(())
+		})
+	}
+}
+
+/// Counterpart to [`crate::parse::parse_null_terminated_vec`].
+/// Appends a zeroed element after the data and includes it in the length if the element size is known.
+pub fn serialize_null_terminated_vec<'inp, T : Serialize + crate::parse::Parse<'inp>>(vec : &[T], output : &mut Output) -> Result<()> {
+	let terminator_size = T::BINARY_SIZE.actual_size(output.is_64_bit);
+	output.write_length(vec.len() + terminator_size.is_some() as usize)?;
+
+	output.write_pointer(|output| {
+		for element in vec {
+			element.serialize(output)?;
+		}
+		if let Some(size) = terminator_size {
+			output.buffer.resize(output.buffer.len() + size, 0);
+		}
+		Ok(())
+	})
+}
+
+/// Counterpart to [`crate::parse::parse_small_vec`].
+pub fn serialize_small_vec<T : Serialize>(vec : &[T], output : &mut Output) -> Result<()> {
+	match u16::try_from(vec.len()) {
+		Ok(length) => output.write_bytes(&length.to_le_bytes()),
+		Err(_) => return Err(Error::ValueTooLarge { r#type: "small array length", actual: vec.len(), max: u16::MAX as usize }),
+	}
+	if vec.is_empty() { output.write_null_pointer(); return Ok(()) }
+
+	output.write_pointer(|output| {
+		for element in vec {
+			element.serialize(output)?;
+		}
+		Ok(())
+	})
+}
+
+impl<T : Serialize, const N : usize> Serialize for [T; N] {
+	fn serialize(&self, output : &mut Output) -> Result<()> {
+		for element in self {
+			element.serialize(output)?;
+		}
+		Ok(())
+	}
+}
+
+/// `serde` only implements `Serialize` for arrays of up to 32 elements.
+/// Use this through `#[serde(serialize_with = "crate::serialize::serde_array")]` for longer ones.
+#[cfg(feature = "serde")]
+pub fn serde_array<T : serde::Serialize, S : serde::Serializer>(array : &[T], serializer : S) -> std::result::Result<S::Ok, S::Error> {
+	serializer.collect_seq(array)
+}
+
+impl Serialize for &[u8] {
+	fn serialize(&self, output : &mut Output) -> Result<()> {
+		output.write_length(self.len())?;
+		if self.is_empty() { output.write_null_pointer(); return Ok(()) }
+
+		output.write_pointer(|output| { output.write_bytes(self); Ok(()) })
+	}
+}
+
+pub trait SerializeVersioned {
+	fn version(&self) -> u16;
+	fn serialize(&self, output : &mut Output) -> Result<()>;
+}
+
+/// Counterpart to [`crate::parse::ParseVariant`].
+pub trait SerializeVariant {
+	fn index(&self) -> u32;
+	fn serialize(&self, output : &mut Output) -> Result<()>;
+}
+
+pub trait SerializeMagicVariant {
+	fn magic(&self) -> u32;
+	fn version(&self) -> u16;
+	fn serialize(&self, output : &mut Output) -> Result<()>;
+}
@@ -0,0 +1,45 @@
+use crate::{parse::{BinarySize, Input, Parse, ParseVariant, Result}, serialize::{Output, Serialize, SerializeVariant}};
+
+/// Tagged union: the index of the active alternative followed by a pointer to its data, the pointer may be null.
+///
+/// `T` is an enum deriving `Parse` with `#[variant]`, its variants are numbered in declaration order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(transparent))]
+pub struct Tagged<T>(pub Option<T>);
+
+impl<'inp, T : ParseVariant<'inp>> Parse<'inp> for Tagged<T> {
+	const BINARY_SIZE : BinarySize = BinarySize{ fixed_bytes: 4, ptrs: 1, is_dynamic: false };
+	fn parse(input : &mut Input<'inp>) -> Result<Self> {
+		let index = u32::parse(input)?;
+		let Some(offset) = input.eat_offset()? else { return Ok(Self(None)) };
+		T::parse(index, &mut input.clone_with_offset(offset)?).map(|value| Self(Some(value)))
+	}
+}
+
+impl<T : SerializeVariant> Serialize for Tagged<T> {
+	fn serialize(&self, output : &mut Output) -> Result<()> {
+		match &self.0 {
+			Some(value) => {
+				value.index().serialize(output)?;
+				output.write_pointer(|output| value.serialize(output))
+			},
+			None => {
+				0u32.serialize(output)?;
+				output.write_null_pointer();
+				Ok(())
+			},
+		}
+	}
+}
+
+impl<T> std::ops::Deref for Tagged<T> {
+	type Target = Option<T>;
+	fn deref(&self) -> &Self::Target { &self.0 }
+}
+impl<T> std::ops::DerefMut for Tagged<T> {
+	fn deref_mut(&mut self) -> &mut Self::Target { &mut self.0 }
+}
+
+impl<T> From<Option<T>> for Tagged<T> {
+	fn from(value : Option<T>) -> Self { Self(value) }
+}
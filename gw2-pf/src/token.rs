@@ -0,0 +1,33 @@
+use crate::{parse::{BinarySize, Input, Parse, Result}, serialize::{Output, Serialize}};
+
+/// Opaque 64 bit identifier, for example of a string or an object.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Token(pub u64);
+
+impl Token {
+	pub fn value(&self) -> u64 { self.0 }
+	pub fn is_zero(&self) -> bool { self.0 == 0 }
+}
+
+impl<'inp> Parse<'inp> for Token {
+	const BINARY_SIZE : BinarySize = u64::BINARY_SIZE;
+	fn parse(input : &mut Input<'inp>) -> Result<Self> { u64::parse(input).map(Self) }
+}
+
+impl Serialize for Token {
+	fn serialize(&self, output : &mut Output) -> Result<()> { self.0.serialize(output) }
+}
+
+impl From<u64> for Token {
+	fn from(value : u64) -> Self { Self(value) }
+}
+
+impl std::fmt::Display for Token {
+	fn fmt(&self, f : &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "{:#018x}", self.0) }
+}
+
+impl std::fmt::Debug for Token {
+	fn fmt(&self, f : &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "Token({self})") }
+}
@@ -0,0 +1,56 @@
+use crate::{parse::{BinarySize, Error, Input, Parse, Result}, serialize::{Output, Serialize}};
+
+/// 16 byte windows GUID, the first three groups are stored little endian.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct UUID(pub [u8; 16]);
+
+impl UUID {
+	pub const NIL : Self = Self([0; 16]);
+
+	pub fn as_bytes(&self) -> &[u8; 16] { &self.0 }
+	pub fn is_nil(&self) -> bool { *self == Self::NIL }
+
+	pub fn data1(&self) -> u32 { u32::from_le_bytes(self.0[0..4].try_into().unwrap()) }
+	pub fn data2(&self) -> u16 { u16::from_le_bytes(self.0[4..6].try_into().unwrap()) }
+	pub fn data3(&self) -> u16 { u16::from_le_bytes(self.0[6..8].try_into().unwrap()) }
+	pub fn data4(&self) -> [u8; 8] { self.0[8..16].try_into().unwrap() }
+}
+
+impl<'inp> Parse<'inp> for UUID {
+	const BINARY_SIZE : BinarySize = BinarySize::fixed(16);
+	fn parse(input : &mut Input<'inp>) -> Result<Self> {
+		if input.remaining.len() < 16 { return Err(Error::to_short::<Self>(input.remaining.len())) }
+		let uuid = Self(input.remaining[..16].try_into().unwrap());
+		input.record_read(16);
+		input.remaining = &input.remaining[16..];
+		Ok(uuid)
+	}
+}
+
+impl Serialize for UUID {
+	fn serialize(&self, output : &mut Output) -> Result<()> {
+		output.write_bytes(&self.0);
+		Ok(())
+	}
+}
+
+/// Formats as `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx`.
+impl std::fmt::Display for UUID {
+	fn fmt(&self, f : &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let d4 = self.data4();
+		write!(f, "{:08x}-{:04x}-{:04x}-{:02x}{:02x}-", self.data1(), self.data2(), self.data3(), d4[0], d4[1])?;
+		for b in &d4[2..] { write!(f, "{b:02x}")? }
+		Ok(())
+	}
+}
+
+impl std::fmt::Debug for UUID {
+	fn fmt(&self, f : &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "UUID({self})") }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for UUID {
+	fn serialize<S : serde::Serializer>(&self, serializer : S) -> std::result::Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
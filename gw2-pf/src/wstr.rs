@@ -1,17 +1,22 @@
-use crate::parse::{BinarySize, Error, Input, Parse, Result};
+use std::ffi::OsString;
+use crate::{parse::{BinarySize, Error, Input, Parse, Result}, serialize::{Output, Serialize}};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct WString(Vec<u16>);
 
 impl<'inp> Parse<'inp> for WString {
 	const BINARY_SIZE : BinarySize = BinarySize::dynamic(); // todo hmmm
 	fn parse(input : &mut Input<'inp>) -> Result<Self> {
-		let u16slice = unsafe { std::slice::from_raw_parts(input.remaining.as_ptr().cast::<u16>(), input.remaining.len() / 2) };
-		match u16slice.iter().position(|c| *c == 0) {
+		// the data is not necessarily aligned to 2 bytes, so it cannot be reinterpreted as a [u16]
+		let chars = input.remaining.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]]));
+		match chars.clone().position(|c| c == 0) {
 			None => Err(Error::CannotFindNullTerminator),
 			Some(terminator_index) => {
+				input.reserve_array::<u16>(terminator_index)?;
+				let string = WString(chars.take(terminator_index).collect());
+				input.record_read((terminator_index + 1) * 2);
 				input.remaining = &input.remaining[(terminator_index + 1) * 2..]; // +1 to skip the actual terminator
-				Ok(WString(u16slice[..terminator_index].to_owned()))
+				Ok(string)
 			},
 		}
 	}
@@ -24,3 +29,122 @@ impl std::ops::Deref for WString {
 impl std::ops::DerefMut for WString {
 	fn deref_mut(&mut self) -> &mut Self::Target { &mut self.0 }
 }
+
+impl Serialize for WString {
+	fn serialize(&self, output : &mut Output) -> Result<()> {
+		for c in self.iter().chain(std::iter::once(&0)) {
+			output.write_bytes(&c.to_le_bytes());
+		}
+		Ok(())
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for WString {
+	fn serialize<S : serde::Serializer>(&self, serializer : S) -> std::result::Result<S::Ok, S::Error> {
+		serializer.serialize_str(&String::from_utf16_lossy(self))
+	}
+}
+
+impl From<Vec<u16>> for WString {
+	fn from(value : Vec<u16>) -> Self { WString(value) }
+}
+
+impl From<WStr<'_>> for WString {
+	fn from(value : WStr<'_>) -> Self { WString(value.chars().collect()) }
+}
+
+/// Borrowed counterpart to [`WString`] that points into the input instead of copying it.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WStr<'inp>(
+	/// Little endian utf16 without the terminator, not necessarily aligned to 2 bytes.
+	&'inp [u8]
+);
+
+impl<'inp> Parse<'inp> for WStr<'inp> {
+	const BINARY_SIZE : BinarySize = BinarySize::dynamic();
+	fn parse(input : &mut Input<'inp>) -> Result<Self> {
+		match input.remaining.chunks_exact(2).position(|c| c == [0, 0]) {
+			None => Err(Error::CannotFindNullTerminator),
+			Some(terminator_index) => {
+				let string = WStr(&input.remaining[..terminator_index * 2]);
+				input.record_read((terminator_index + 1) * 2);
+				input.remaining = &input.remaining[(terminator_index + 1) * 2..]; // +1 to skip the actual terminator
+				Ok(string)
+			},
+		}
+	}
+}
+
+impl<'inp> WStr<'inp> {
+	/// Number of utf16 code units.
+	pub fn len(&self) -> usize { self.0.len() / 2 }
+	pub fn is_empty(&self) -> bool { self.0.is_empty() }
+
+	pub fn get(&self, index : usize) -> Option<u16> {
+		let start = index.checked_mul(2)?;
+		self.0.get(start..start.checked_add(2)?).map(|c| u16::from_le_bytes([c[0], c[1]]))
+	}
+
+	pub fn chars(&self) -> impl Iterator<Item = u16> + Clone + 'inp {
+		self.0.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]]))
+	}
+
+	/// The raw little endian bytes, without the terminator.
+	pub fn as_bytes(&self) -> &'inp [u8] { self.0 }
+
+	/// Invalid surrogates are replaced with U+FFFD.
+	pub fn to_string_lossy(&self) -> String {
+		char::decode_utf16(self.chars()).map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)).collect()
+	}
+
+	/// Lossless on windows, elsewhere invalid surrogates are replaced with U+FFFD.
+	pub fn to_os_string(&self) -> OsString {
+		#[cfg(windows)] {
+			use std::os::windows::ffi::OsStringExt;
+			OsString::from_wide(&self.chars().collect::<Vec<_>>())
+		}
+		#[cfg(not(windows))] {
+			OsString::from(self.to_string_lossy())
+		}
+	}
+}
+
+impl std::fmt::Display for WStr<'_> {
+	fn fmt(&self, f : &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		use std::fmt::Write;
+		for c in char::decode_utf16(self.chars()) {
+			f.write_char(c.unwrap_or(char::REPLACEMENT_CHARACTER))?;
+		}
+		Ok(())
+	}
+}
+
+impl std::fmt::Debug for WStr<'_> {
+	fn fmt(&self, f : &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		std::fmt::Debug::fmt(&self.to_string_lossy(), f)
+	}
+}
+
+impl From<WStr<'_>> for String {
+	fn from(value : WStr<'_>) -> Self { value.to_string_lossy() }
+}
+
+impl From<WStr<'_>> for OsString {
+	fn from(value : WStr<'_>) -> Self { value.to_os_string() }
+}
+
+impl Serialize for WStr<'_> {
+	fn serialize(&self, output : &mut Output) -> Result<()> {
+		output.write_bytes(self.0);
+		output.write_bytes(&[0, 0]);
+		Ok(())
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for WStr<'_> {
+	fn serialize<S : serde::Serializer>(&self, serializer : S) -> std::result::Result<S::Ok, S::Error> {
+		serializer.serialize_str(&self.to_string_lossy())
+	}
+}
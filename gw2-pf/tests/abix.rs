@@ -18,8 +18,31 @@ fn deserialize() {
 
 	assert_eq!(chunk.bank_language.len(), 6);
 	assert_eq!(chunk.bank_language[0].bank_file_name.len(), 43769);
-	assert_eq!(chunk.bank_language[0].bank_file_name[0].file_name.as_ref().unwrap().to_id(), 157442);
-	assert_eq!(chunk.bank_language[0].bank_file_name[1000].file_name.as_ref().unwrap().to_id(), 0);
+	let file_name = |i| chunk.bank_language[0].bank_file_name.get(i).unwrap().map_err(|e| e.to_string()).unwrap().file_name;
+	assert_eq!(file_name(0).unwrap().to_id(), 157442);
+	assert_eq!(file_name(1000).unwrap().to_id(), 0);
+	assert!(chunk.bank_language[0].bank_file_name.get(43769).is_none());
+}
+
+#[test]
+fn lazy_matches_eager() {
+	let data = {
+		let mut file = File::open("tests/res/184691").unwrap();
+		let mut v = Vec::new();
+		file.read_to_end(&mut v).unwrap();
+		v
+	};
+
+	let chunk = dut::pf::PackFileReader::<dut::formats::ABIX>::from_bytes(&data).map_err(|e| e.to_string()).unwrap().next().unwrap().map_err(|e| e.to_string()).unwrap();
+	for language in &chunk.bank_language {
+		let all = language.bank_file_name.to_vec().map_err(|e| e.to_string()).unwrap();
+		assert_eq!(all.len(), language.bank_file_name.len());
+		if all.is_empty() { continue }
+		for i in [0, 1, all.len() / 2, all.len() - 1] {
+			let lazy = language.bank_file_name.get(i).unwrap().map_err(|e| e.to_string()).unwrap();
+			assert_eq!(lazy.file_name.map(|n| n.to_id()), all[i].file_name.as_ref().map(|n| n.to_id()));
+		}
+	}
 }
 
 #[test] #[ignore = "long runtime"]
@@ -55,6 +78,7 @@ fn find_voice_id() {
 
 			for (lang_idx, maps) in chunk.bank_language.iter().enumerate() {
 				for (data_idx, data) in maps.bank_file_name.iter().enumerate() {
+					let data = data.unwrap();
 					if data_idx as u32 == search || matches!(data.file_name, Some(ref n) if n.to_id() == search) {
 						println!("lang: {lang_idx}, data: {data_idx}, {:?}", data.file_name.as_ref().map(|n| n.to_id()));
 						found = true;
@@ -0,0 +1,261 @@
+use gw2_pf_rs as dut;
+use dut::{export::AudioMetadata, formats::asnd::asnd::v2::{AudioFormat, PayloadKind, WaveformData}, parse::{Error, Input, Parse}, serialize::{Output, Serialize}};
+
+fn payload_kinds(path : &str) -> Vec<PayloadKind> {
+	let data = std::fs::read(path).unwrap();
+	let mut kinds = Vec::new();
+	for bank in dut::pf::PackFileReader::<dut::formats::ABNK>::from_bytes(&data).map_err(|e| e.to_string()).unwrap() {
+		let bank = bank.map_err(|e| e.to_string()).unwrap();
+		for file in bank.files.iter().filter(|f| !f.audio_data.is_empty()) {
+			for waveform in dut::pf::PackFileReader::<dut::formats::ASND>::from_bytes(file.audio_data).map_err(|e| e.to_string()).unwrap() {
+				let waveform = waveform.map_err(|e| e.to_string()).unwrap();
+				assert_eq!(waveform.audio_format(), AudioFormat::Mp3);
+				assert_ne!(waveform.crc, 0);
+				kinds.push(waveform.detect_payload_kind());
+			}
+		}
+	}
+	kinds
+}
+
+#[test]
+fn detect_payload_kind() {
+	let kinds = payload_kinds("tests/res/179764.abnk");
+	assert_eq!(kinds.len(), 10);
+	assert!(kinds.iter().all(|k| *k == PayloadKind::Mp3));
+
+	// the crc is computed over the plain data, so none of the encrypted waveforms match theirs
+	let kinds = payload_kinds("tests/res/3127451.abnk");
+	assert_eq!(kinds.len(), 10);
+	assert!(kinds.iter().all(|k| *k == PayloadKind::Encrypted));
+}
+
+#[test]
+fn bank_waveforms() {
+	let data = std::fs::read("tests/res/179764.abnk").unwrap();
+	let mut banks = dut::pf::PackFileReader::<dut::formats::ABNK>::from_bytes(&data).map_err(|e| e.to_string()).unwrap();
+	let bank = banks.next().unwrap().map_err(|e| e.to_string()).unwrap();
+
+	let waveforms = bank.waveforms().collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string()).unwrap();
+	assert_eq!(waveforms.len(), 10);
+	for waveform in &waveforms {
+		assert_eq!(waveform.payload_kind, PayloadKind::Mp3);
+		assert_eq!(waveform.voice_id, waveform.entry.voice_id);
+		assert!(!waveform.waveform.audio_data.is_empty());
+		assert!(waveform.waveform.crc_matches());
+	}
+	assert_eq!(waveforms.iter().map(|w| w.index).collect::<Vec<_>>(), [0; 10]);
+}
+
+#[test]
+fn bank_waveforms_continue_after_errors() {
+	let data = std::fs::read("tests/res/179764.abnk").unwrap();
+	let garbage = [0u8; 16];
+	let mut banks = dut::pf::PackFileReader::<dut::formats::ABNK>::from_bytes(&data).map_err(|e| e.to_string()).unwrap();
+	let mut bank = banks.next().unwrap().map_err(|e| e.to_string()).unwrap();
+
+	let entries = bank.files.iter().enumerate().filter(|(_, f)| !f.audio_data.is_empty()).map(|(i, _)| i).collect::<Vec<_>>();
+	let (broken, truncated) = (entries[1], entries[4]);
+	bank.files[broken].audio_data = &garbage;
+	// the header stays intact, only the chunk data is cut off
+	let audio_data = bank.files[truncated].audio_data;
+	bank.files[truncated].audio_data = &audio_data[..audio_data.len() / 2];
+
+	let results = bank.waveforms().collect::<Vec<_>>();
+	assert_eq!(results.len(), 10);
+	for (result, entry) in results.iter().zip(&entries) {
+		let voice_id = bank.files[*entry].voice_id;
+		match result {
+			Ok(waveform) => {
+				assert!(*entry != broken && *entry != truncated);
+				assert_eq!(waveform.voice_id, voice_id);
+				assert_eq!(waveform.payload_kind, PayloadKind::Mp3);
+			},
+			Err(error) => {
+				assert_eq!(error.voice_id, voice_id);
+				match error.index {
+					None    => assert_eq!(*entry, broken),
+					Some(0) => assert_eq!(*entry, truncated),
+					Some(i) => panic!("unexpected waveform index {i}"),
+				}
+				assert!(error.to_string().starts_with(&format!("voice {voice_id}")));
+			},
+		}
+	}
+	assert_eq!(results.iter().filter(|r| r.is_err()).count(), 2);
+}
+
+#[test]
+fn detect_magic_bytes() {
+	assert_eq!(PayloadKind::detect(b"OggS\0\x02"), Some(PayloadKind::Ogg));
+	assert_eq!(PayloadKind::detect(b"RIFF\x24\0\0\0WAVEfmt "), Some(PayloadKind::Wav));
+	assert_eq!(PayloadKind::detect(b"RIFF\x24\0\0\0AVI "), None);
+	assert_eq!(PayloadKind::detect(b"ID3\x04\0"), Some(PayloadKind::Mp3));
+	assert_eq!(PayloadKind::detect(&[0xff, 0xfb, 0x94, 0x64]), Some(PayloadKind::Mp3));
+	// sync bits, but reserved version, layer, bitrate or sample rate
+	assert_eq!(PayloadKind::detect(&[0xff, 0xeb, 0x94]), None);
+	assert_eq!(PayloadKind::detect(&[0xff, 0xf9, 0x94]), None);
+	assert_eq!(PayloadKind::detect(&[0xff, 0xfb, 0xf4]), None);
+	assert_eq!(PayloadKind::detect(&[0xff, 0xfb, 0x9c]), None);
+	assert_eq!(PayloadKind::detect(&[]), None);
+
+	assert_eq!(PayloadKind::Mp3.extension(), "mp3");
+	assert_eq!(PayloadKind::Encrypted.extension(), "bin");
+	assert_eq!(PayloadKind::Unknown.extension(), "bin");
+}
+
+#[test]
+fn audio_format() {
+	for value in 0..=255u8 {
+		assert_eq!(u8::from(AudioFormat::from(value)), value);
+	}
+	assert_eq!(AudioFormat::from(1), AudioFormat::Mp3);
+	assert_eq!(AudioFormat::from(7), AudioFormat::Unknown(7));
+
+	assert_eq!(AudioFormat::from(0), AudioFormat::Unknown(0));
+}
+
+/// ASND v2 chunk data with the given header values and payload.
+fn waveform_data(format : u8, num_channels : u8, num_samples : u32, length : f32, loop_range : (u32, u32), audio : &[u8]) -> Vec<u8> {
+	let mut output = Output::new(false);
+	for value in [length, 0.0] { value.serialize(&mut output).unwrap() }
+	output.write_null_pointer();
+	for value in [0, 0, 0, num_samples, loop_range.0, loop_range.1, 0] { value.serialize(&mut output).unwrap() }
+	for value in [format, 0, 0, 0, num_channels, 0, 0, 0] { value.serialize(&mut output).unwrap() }
+	audio.serialize(&mut output).unwrap();
+	(&[][..]).serialize(&mut output).unwrap();
+	output.finish().unwrap()
+}
+
+fn riff_chunk<'a>(wav : &'a [u8], id : &[u8; 4]) -> Option<&'a [u8]> {
+	let mut rest = &wav[12..];
+	while rest.len() >= 8 {
+		let size = u32::from_le_bytes(rest[4..8].try_into().unwrap()) as usize;
+		if &rest[..4] == id { return Some(&rest[8..8 + size]) }
+		rest = &rest[(8 + size + size % 2).min(rest.len())..];
+	}
+	None
+}
+
+#[test]
+fn wav_export() {
+	let samples = (0..4800u32 * 2).flat_map(|i| (i as i16).to_le_bytes()).collect::<Vec<_>>();
+	let data = waveform_data(0, 2, 4800, 0.1, (100, 4000), &samples);
+	let waveform = WaveformData::parse(&mut Input::new(&data, false)).map_err(|e| e.to_string()).unwrap();
+	assert_eq!(waveform.detect_payload_kind(), PayloadKind::Unknown);
+	assert_eq!(waveform.sample_rate(), Some(48000));
+
+	let wav = dut::export::wav(&waveform).map_err(|e| e.to_string()).unwrap();
+	assert_eq!(&wav[..4], b"RIFF");
+	assert_eq!(u32::from_le_bytes(wav[4..8].try_into().unwrap()) as usize, wav.len() - 8);
+	assert_eq!(&wav[8..12], b"WAVE");
+
+	let fmt = riff_chunk(&wav, b"fmt ").unwrap();
+	let fmt_u16 = |at : usize| u16::from_le_bytes(fmt[at..at + 2].try_into().unwrap());
+	let fmt_u32 = |at : usize| u32::from_le_bytes(fmt[at..at + 4].try_into().unwrap());
+	assert_eq!(fmt_u16(0), 1); // PCM
+	assert_eq!(fmt_u16(2), 2); // channels
+	assert_eq!(fmt_u32(4), 48000); // sample rate
+	assert_eq!(fmt_u32(8), 48000 * 4); // byte rate
+	assert_eq!(fmt_u16(12), 4); // block align
+	assert_eq!(fmt_u16(14), 16); // bits per sample
+
+	let smpl = riff_chunk(&wav, b"smpl").unwrap();
+	let smpl_u32 = |index : usize| u32::from_le_bytes(smpl[index * 4..index * 4 + 4].try_into().unwrap());
+	assert_eq!(smpl.len(), 60);
+	assert_eq!(smpl_u32(7), 1); // loop count
+	assert_eq!((smpl_u32(11), smpl_u32(12)), (100, 3999));
+
+	assert_eq!(riff_chunk(&wav, b"data").unwrap(), samples);
+}
+
+#[test]
+fn wav_export_without_loop() {
+	let data = waveform_data(0, 0, 3, 0.5, (0, 0), &[1, 2, 3, 4, 5, 6]);
+	let waveform = WaveformData::parse(&mut Input::new(&data, false)).map_err(|e| e.to_string()).unwrap();
+	let wav = dut::export::wav(&waveform).map_err(|e| e.to_string()).unwrap();
+	assert!(riff_chunk(&wav, b"smpl").is_none());
+	assert_eq!(riff_chunk(&wav, b"fmt ").unwrap()[2], 1); // mono
+	assert_eq!(riff_chunk(&wav, b"fmt ").unwrap()[14], 16);
+	assert_eq!(riff_chunk(&wav, b"data").unwrap(), [1, 2, 3, 4, 5, 6]);
+	assert_eq!(wav.len() % 2, 0);
+}
+
+#[test]
+fn wav_export_rejects() {
+	let export = |data : Vec<u8>| {
+		let waveform = WaveformData::parse(&mut Input::new(&data, false)).map_err(|e| e.to_string()).unwrap();
+		dut::export::wav(&waveform)
+	};
+	// compressed
+	assert!(matches!(export(waveform_data(1, 1, 2, 1.0, (0, 0), &[0xff, 0xfb, 0x94, 0x64])), Err(Error::UnsupportedAudio { .. })));
+	// no length
+	assert!(matches!(export(waveform_data(0, 1, 2, 0.0, (0, 0), &[0; 4])), Err(Error::UnsupportedAudio { .. })));
+	// size does not match the sample count
+	assert!(matches!(export(waveform_data(0, 1, 2, 1.0, (0, 0), &[0; 5])), Err(Error::UnsupportedAudio { .. })));
+	assert!(matches!(export(waveform_data(0, 1, 2, 1.0, (0, 0), &[0; 10])), Err(Error::UnsupportedAudio { .. })));
+	// 8 bit samples
+	assert!(matches!(export(waveform_data(0, 1, 2, 1.0, (0, 0), &[0; 2])), Err(Error::UnsupportedAudio { .. })));
+	// sample rate * block align does not fit into the byte rate
+	assert!(matches!(export(waveform_data(0, 2, 2, 1e-9, (0, 0), &[0; 8])), Err(Error::UnsupportedAudio { .. })));
+}
+
+#[test]
+fn metadata() {
+	let data = std::fs::read("tests/res/179764.abnk").unwrap();
+	let bank = dut::pf::PackFileReader::<dut::formats::ABNK>::from_bytes(&data).map_err(|e| e.to_string()).unwrap()
+		.next().unwrap().map_err(|e| e.to_string()).unwrap();
+	let entry = &bank.files[0];
+	let waveform = dut::pf::PackFileReader::<dut::formats::ASND>::from_bytes(entry.audio_data).map_err(|e| e.to_string()).unwrap()
+		.next().unwrap().map_err(|e| e.to_string()).unwrap();
+
+	let metadata = AudioMetadata::from_waveform(&waveform).with_bank_entry(entry);
+	assert_eq!(metadata.voice_id, Some(14940));
+	assert_eq!(metadata.sample_rate, Some(48000));
+	assert_eq!(metadata.loop_start, None);
+	assert_eq!(metadata.loop_length, None);
+	assert_eq!(metadata.num_samples, 332729);
+	assert!((metadata.length - 6.9318542).abs() < 1e-6);
+
+	let data = waveform_data(0, 1, 200, 1.0, (10, 110), &[0; 400]);
+	let waveform = WaveformData::parse(&mut Input::new(&data, false)).map_err(|e| e.to_string()).unwrap();
+	let looping = AudioMetadata::from_waveform(&waveform);
+	assert_eq!((looping.loop_start, looping.loop_end, looping.loop_length), (Some(10), Some(110), Some(100)));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn metadata_json() {
+	let looping = AudioMetadata { voice_id: Some(14940), loop_start: Some(10), loop_end: Some(110), loop_length: Some(100), length: f32::NAN, ..Default::default() };
+	let json = serde_json::to_value(&looping).unwrap();
+	assert_eq!(json["voice_id"], 14940);
+	assert_eq!((&json["loop_start"], &json["loop_end"], &json["loop_length"]), (&10.into(), &110.into(), &100.into()));
+	assert_eq!(json["length"], serde_json::Value::Null);
+}
+
+#[test]
+fn mp3_id3_tag() {
+	let payload = [0xff, 0xfb, 0x94, 0x64, 1, 2, 3];
+	let metadata = AudioMetadata { length: 1.5, loop_start: Some(10), loop_end: Some(110), loop_length: Some(100), ..Default::default() };
+	let file = dut::export::mp3_with_metadata(&payload, &metadata).map_err(|e| e.to_string()).unwrap();
+
+	assert_eq!(&file[..6], b"ID3\x03\x00\x00");
+	let size = file[6..10].iter().fold(0usize, |size, b| size << 7 | *b as usize);
+	assert_eq!(&file[10 + size..], &payload);
+
+	let mut frames = Vec::new();
+	let mut rest = &file[10..10 + size];
+	while !rest.is_empty() {
+		let frame_size = u32::from_be_bytes(rest[4..8].try_into().unwrap()) as usize;
+		frames.push((String::from_utf8_lossy(&rest[..4]).into_owned(), String::from_utf8_lossy(&rest[11..10 + frame_size]).into_owned()));
+		rest = &rest[10 + frame_size..];
+	}
+	assert_eq!(frames, [
+		("TLEN".to_string(), "1500".to_string()),
+		("TXXX".to_string(), "LOOPSTART\u{0}10".to_string()),
+		("TXXX".to_string(), "LOOPLENGTH\u{0}100".to_string()),
+	]);
+
+	assert!(matches!(dut::export::mp3_with_metadata(b"OggS", &metadata), Err(Error::UnsupportedAudio { .. })));
+	assert!(matches!(dut::export::mp3_with_metadata(&file, &metadata), Err(Error::UnsupportedAudio { .. })));
+}
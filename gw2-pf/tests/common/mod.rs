@@ -0,0 +1,132 @@
+//! Compressor producing streams for `gw2_pf_rs::compression`.
+//! It only uses a fixed pair of trees and greedy matching, so it is far from optimal, but it exercises literals, copies, multiple blocks and crc dwords.
+
+#![allow(dead_code)]
+
+const CRC_INTERVAL : usize = 0x4000;
+const CODES_PER_BLOCK : usize = 0x10000;
+const MIN_MATCH : usize = 3;
+const MAX_MATCH : usize = 256;
+const MAX_OFFSET : usize = 0x20000;
+
+struct BitWriter {
+	words          : Vec<u32>,
+	current        : u64,
+	bits_available : u8,
+}
+
+impl BitWriter {
+	fn write(&mut self, value : u32, bits : u8) {
+		if bits == 0 { return }
+		self.current = (self.current << bits) | (value as u64 & ((1 << bits) - 1));
+		self.bits_available += bits;
+		while self.bits_available >= 32 {
+			self.bits_available -= 32;
+			self.push_word((self.current >> self.bits_available) as u32);
+		}
+	}
+
+	fn push_word(&mut self, word : u32) {
+		if (self.words.len() + 1).is_multiple_of(CRC_INTERVAL) { self.words.push(0) }
+		self.words.push(word);
+	}
+
+	fn finish(mut self) -> Vec<u8> {
+		if self.bits_available > 0 {
+			let word = (self.current << (32 - self.bits_available)) as u32;
+			self.push_word(word);
+		}
+		self.words.iter().flat_map(|w| w.to_le_bytes()).collect()
+	}
+}
+
+/// Splits `value` into a tree symbol and extra bits for tables of the form `base(code) = (1 << (quot - 1)) * (step + rem)`.
+fn split_value(value : usize, step : usize, max_code : u16) -> (u16, u32, u8) {
+	for code in 0..=max_code {
+		let (quot, rem) = ((code / step as u16) as usize, (code % step as u16) as usize);
+		let (base, extra_bits) = match quot {
+			0 => (code as usize, 0),
+			_ => ((1 << (quot - 1)) * (step + rem), quot - 1),
+		};
+		if value >= base && value < base + (1 << extra_bits) {
+			return (code, (value - base) as u32, extra_bits as u8)
+		}
+	}
+	panic!("value {value} cannot be encoded")
+}
+
+fn write_trees(writer : &mut BitWriter) {
+	// dictionary tree codes for the code length runs used below
+	const NINE_BITS_EIGHT_TIMES : (u32, u8) = (0b0000001100, 10); // 0xE9
+	const NINE_BITS_FIVE_TIMES  : (u32, u8) = (0b000010000, 9); // 0x89
+	const SIX_BITS_EIGHT_TIMES  : (u32, u8) = (0b0000000011000, 13); // 0xE6
+	const SIX_BITS_TWICE        : (u32, u8) = (0b0001100, 7); // 0x26
+
+	// all 285 symbols with 9 bits, assigned from the top so symbol `s` gets code `511 - s`
+	writer.write(285, 16);
+	for _ in 0..35 { writer.write(NINE_BITS_EIGHT_TIMES.0, NINE_BITS_EIGHT_TIMES.1) }
+	writer.write(NINE_BITS_FIVE_TIMES.0, NINE_BITS_FIVE_TIMES.1);
+
+	// all 34 offset codes with 6 bits, code `c` becomes `63 - c`
+	writer.write(34, 16);
+	for _ in 0..4 { writer.write(SIX_BITS_EIGHT_TIMES.0, SIX_BITS_EIGHT_TIMES.1) }
+	writer.write(SIX_BITS_TWICE.0, SIX_BITS_TWICE.1);
+
+	writer.write(0xf, 4); // (0xf + 1) << 12 codes per block
+}
+
+pub fn compress(data : &[u8]) -> Vec<u8> {
+	let mut writer = BitWriter { words: Vec::new(), current: 0, bits_available: 0 };
+	writer.write(0, 32);
+	writer.write(data.len() as u32, 32);
+	if data.is_empty() { return writer.finish() }
+
+	writer.write(0, 4);
+	writer.write(0, 4); // copy sizes get + 1
+
+	let mut last_seen = std::collections::HashMap::<[u8; 3], usize>::new();
+	let mut codes_in_block = CODES_PER_BLOCK;
+	let mut position = 0;
+	while position < data.len() {
+		if codes_in_block == CODES_PER_BLOCK {
+			write_trees(&mut writer);
+			codes_in_block = 0;
+		}
+		codes_in_block += 1;
+
+		let mut match_length = 0;
+		let mut match_offset = 0;
+		if position + MIN_MATCH <= data.len() {
+			let key : [u8; 3] = data[position..][..3].try_into().unwrap();
+			if let Some(&start) = last_seen.get(&key) {
+				if position - start <= MAX_OFFSET {
+					match_length = data[start..].iter().zip(&data[position..]).take(MAX_MATCH).take_while(|(a, b)| a == b).count();
+					match_offset = position - start;
+				}
+			}
+		}
+
+		if match_length >= MIN_MATCH {
+			let (code, extra, extra_bits) = split_value(match_length - 1, 4, 27);
+			writer.write(511 - (0x100 + code as u32), 9);
+			writer.write(extra, extra_bits);
+
+			let (code, extra, extra_bits) = split_value(match_offset - 1, 2, 33);
+			writer.write(63 - code as u32, 6);
+			writer.write(extra, extra_bits);
+		}
+		else {
+			match_length = 1;
+			writer.write(511 - data[position] as u32, 9);
+		}
+
+		for p in position..position + match_length {
+			if p + MIN_MATCH <= data.len() {
+				last_seen.insert(data[p..][..3].try_into().unwrap(), p);
+			}
+		}
+		position += match_length;
+	}
+
+	writer.finish()
+}
@@ -0,0 +1,84 @@
+use std::io::Cursor;
+use gw2_pf_rs as dut;
+
+mod common;
+
+/// Builds a minimal archive: header, file contents, MFT and file id table.
+/// `files` are `(file_id, compression_flag, contents)`.
+fn build_archive(files : &[(u32, u16, &[u8])]) -> Vec<u8> {
+	let mut archive = vec![0u8; 40]; // header, filled in at the end
+
+	// records 0..=2 are the mft header, archive header and id table, record 0 is not written as an entry
+	let mut entries = vec![(0u64, 40u32, 0u16)];
+	let mut id_table = Vec::new();
+	for (i, (file_id, compression_flag, contents)) in files.iter().enumerate() {
+		let mft_index = 3 + i as u32;
+		id_table.extend_from_slice(&file_id.to_le_bytes());
+		id_table.extend_from_slice(&mft_index.to_le_bytes());
+		entries.push((archive.len() as u64, contents.len() as u32, *compression_flag));
+		archive.extend_from_slice(contents);
+	}
+	// an unused slot, as found in real archives
+	id_table.extend_from_slice(&[0; 8]);
+	entries.insert(1, (archive.len() as u64, id_table.len() as u32, 0));
+	archive.extend_from_slice(&id_table);
+
+	let mft_offset = archive.len() as u64;
+	archive.extend_from_slice(b"Mft\x1a");
+	archive.extend_from_slice(&0u64.to_le_bytes());
+	archive.extend_from_slice(&(entries.len() as u32 + 1).to_le_bytes());
+	archive.extend_from_slice(&[0; 8]);
+	for (offset, size, compression_flag) in entries {
+		archive.extend_from_slice(&offset.to_le_bytes());
+		archive.extend_from_slice(&size.to_le_bytes());
+		archive.extend_from_slice(&compression_flag.to_le_bytes());
+		archive.extend_from_slice(&[0; 2 + 4 + 4]);
+	}
+	let mft_size = (archive.len() as u64 - mft_offset) as u32;
+
+	let header = &mut archive[..40];
+	header[..4].copy_from_slice(b"\x97AN\x1a");
+	header[4..8].copy_from_slice(&40u32.to_le_bytes());
+	header[12..16].copy_from_slice(&0x200u32.to_le_bytes());
+	header[24..32].copy_from_slice(&mft_offset.to_le_bytes());
+	header[32..36].copy_from_slice(&mft_size.to_le_bytes());
+
+	archive
+}
+
+#[test]
+fn read_files_by_id() {
+	let txtv = std::fs::read("tests/res/198300.txtv").unwrap();
+	let abix = std::fs::read("tests/res/184691").unwrap();
+	let sound = std::fs::read("tests/res/2788751.sound").unwrap();
+	let archive = build_archive(&[(198300, 0, &txtv), (184691, 0, &abix), (1234, 8, &common::compress(&sound))]);
+
+	let dat = &mut dut::dat::DatFile::open(Cursor::new(archive)).map_err(|e| e.to_string()).unwrap();
+	assert_eq!(dat.header.version(), 0x97);
+	assert_eq!(dat.file_ids().count(), 3);
+
+	let data = dat.read_file(198300).map_err(|e| e.to_string()).unwrap();
+	assert_eq!(data, txtv);
+	let file = dut::pf::PackFileReader::<dut::formats::txtv>::from_bytes(&data).map_err(|e| e.to_string()).unwrap();
+	assert_eq!(file.count(), 1);
+
+	let data = dat.read_file(184691).map_err(|e| e.to_string()).unwrap();
+	let file = &mut dut::pf::PackFileReader::<dut::formats::ABIX>::from_bytes(&data).map_err(|e| e.to_string()).unwrap();
+	let chunk = file.next().unwrap().map_err(|e| e.to_string()).unwrap();
+	assert_eq!(chunk.bank_language.len(), 6);
+
+	assert!(dat.entry(1234).unwrap().is_compressed());
+	assert_eq!(dat.read_file(1234).map_err(|e| e.to_string()).unwrap(), sound);
+	assert!(matches!(dat.read_file(42), Err(dut::dat::Error::FileNotFound { file_id: 42 })));
+}
+
+#[test]
+fn reject_invalid_archive() {
+	let mut archive = build_archive(&[]);
+	archive[1] = b'X';
+	assert!(matches!(dut::dat::DatFile::open(Cursor::new(archive)), Err(dut::dat::Error::Parse(dut::parse::Error::InvalidFileType { .. }))));
+
+	let mut archive = build_archive(&[]);
+	archive.truncate(60);
+	assert!(matches!(dut::dat::DatFile::open(Cursor::new(archive)), Err(dut::dat::Error::Parse(dut::parse::Error::DataTooShort { .. }))));
+}
@@ -0,0 +1,118 @@
+//! Exercises the derive with code generated by gw2-pf-typegen from `gw2-pf-typegen/tests/res/field_kinds.schema`.
+//! The generated code refers to `crate::`, so the paths it uses are imported into the root of this test.
+
+use gw2_pf_rs as dut;
+use dut::{fcc, parse, pf, serialize, CString, FileName, Tagged};
+use gw2_pf_rs_derive::Parse;
+use parse::{Error, Input, Parse as _, ParseVersioned};
+use serialize::{Output, Serialize};
+
+#[path = "generated/fknd/fknd.rs"]
+#[allow(clippy::upper_case_acronyms)]
+mod fknd;
+use fknd::v0::{Circle, FieldKinds, Label, Named, Point, Variant_5085761950254463568 as Shape};
+
+fn field_kinds<'a>(shape : Option<Shape<'a>>) -> FieldKinds<'a> {
+	FieldKinds {
+		inline     : [1, 2, 3],
+		fixed      : [Point { x: 1.0, y: 2.0 }, Point { x: 3.0, y: 4.0 }],
+		long       : std::array::from_fn(|i| i as u8),
+		pointers   : vec![Some(Named { name: FileName::from_id(5) }), None, Some(Named { name: None })],
+		small      : vec![10, 20, 30],
+		small_bytes: vec![7; 300],
+		data       : b"data",
+		shape      : Tagged(shape),
+		origin     : Some(Point { x: -1.0, y: -2.0 }),
+	}
+}
+
+/// serialize -> parse -> serialize, both the values and the written data have to match
+fn round_trip(value : &FieldKinds, is_64_bit : bool) -> Vec<u8> {
+	let mut output = Output::new(is_64_bit);
+	value.serialize(&mut output).map_err(|e| e.to_string()).unwrap();
+	let data = output.finish().map_err(|e| e.to_string()).unwrap();
+
+	let parsed = FieldKinds::parse(&mut Input::new(&data, is_64_bit)).map_err(|e| e.to_string()).unwrap();
+	assert_eq!(format!("{parsed:?}"), format!("{value:?}"));
+
+	let mut output = Output::new(is_64_bit);
+	parsed.serialize(&mut output).map_err(|e| e.to_string()).unwrap();
+	assert_eq!(output.finish().map_err(|e| e.to_string()).unwrap(), data);
+	data
+}
+
+#[test]
+fn binary_size() {
+	// inline arrays are stored in place, small arrays have a 16 bit length
+	assert_eq!(FieldKinds::BINARY_SIZE.actual_size(false), Some(6 + 16 + 40 + 8 + 6 + 6 + 8 + 8 + 4));
+	assert_eq!(FieldKinds::BINARY_SIZE.actual_size(true), Some(6 + 16 + 40 + 12 + 10 + 10 + 12 + 12 + 8));
+}
+
+#[test]
+fn field_kinds_round_trip() {
+	let value = field_kinds(Some(Shape::Var0(Circle { center: Point { x: 5.0, y: 6.0 }, radius: 7.0 })));
+	for is_64_bit in [false, true] {
+		round_trip(&value, is_64_bit);
+	}
+
+	let data = round_trip(&value, false);
+	assert_eq!(&data[..6], &[1, 0, 2, 0, 3, 0]);
+	assert_eq!(&data[22..62], &value.long);
+	assert_eq!(&data[62..66], &3u32.to_le_bytes()); // pointers
+	assert_eq!(&data[70..72], &3u16.to_le_bytes()); // small
+	assert_eq!(&data[76..78], &300u16.to_le_bytes()); // small_bytes
+	assert_eq!(&data[90..94], &0u32.to_le_bytes()); // shape index
+}
+
+#[test]
+fn variants() {
+	let label = Label::parse(&mut Input::new(&[0; 4], false)).map_err(|e| e.to_string()).unwrap();
+	assert!(label.text.is_null());
+	let data = round_trip(&field_kinds(Some(Shape::Var1(label))), false);
+	assert_eq!(&data[90..94], &1u32.to_le_bytes());
+
+	let data = round_trip(&field_kinds(None), false);
+	assert_eq!(&data[90..98], &[0; 8]);
+
+	let mut data = data;
+	data[90..94].copy_from_slice(&2u32.to_le_bytes());
+	data[94..98].copy_from_slice(&4u32.to_le_bytes());
+	let error = FieldKinds::parse(&mut Input::new(&data, false)).unwrap_err();
+	assert!(matches!(error.innermost(), Error::UnknownVariant { actual: 2, .. }), "{error}");
+	assert!(error.to_string().starts_with("shape @ 0x5a"), "{error}");
+}
+
+#[test]
+fn small_array_length() {
+	let mut value = field_kinds(None);
+	value.small = vec![0; u16::MAX as usize + 1];
+	let mut output = Output::new(false);
+	assert!(matches!(value.serialize(&mut output), Err(Error::ValueTooLarge { .. })));
+}
+
+#[test]
+fn inline_array_too_short() {
+	let error = <[u32; 4]>::parse(&mut Input::new(&[0; 15], false)).unwrap_err();
+	assert!(matches!(error, Error::DataTooShort { required: 16, actual: 15, .. }), "{error}");
+}
+
+#[test]
+fn chunk() {
+	let value = field_kinds(None);
+	let mut output = Output::new(false);
+	value.serialize(&mut output).map_err(|e| e.to_string()).unwrap();
+	let data = output.finish().map_err(|e| e.to_string()).unwrap();
+
+	let chunk = <fknd::FKND as ParseVersioned>::parse(0, &mut Input::new(&data, false)).map_err(|e| e.to_string()).unwrap();
+	assert_eq!(chunk.small, [10, 20, 30]);
+	assert_eq!(<fknd::FKND as pf::Magic>::MAGIC, fcc(b"FKND"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn field_kinds_serde() {
+	let json = serde_json::to_value(field_kinds(None)).unwrap();
+	assert_eq!(json["long"].as_array().unwrap().len(), 40);
+	assert_eq!(json["pointers"], serde_json::json!([{ "name": 5 }, null, { "name": null }]));
+	assert_eq!(json["shape"], serde_json::Value::Null);
+}
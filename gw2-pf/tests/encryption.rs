@@ -0,0 +1,47 @@
+use gw2_pf_rs as dut;
+use dut::{encryption::{decrypt, derive_rc4_key, Rc4}, parse::Error};
+
+#[test]
+fn rc4() {
+	let mut data = *b"Plaintext";
+	Rc4::new(b"Key").apply(&mut data);
+	assert_eq!(data, [0xbb, 0xf3, 0x16, 0xe8, 0xd9, 0x40, 0xaf, 0x0a, 0xd3]);
+}
+
+#[test]
+fn key_derivation() {
+	assert_eq!(derive_rc4_key(1), [0x74, 0x64, 0x6b, 0xa9, 0x7d, 0xbb, 0xed, 0x8d, 0x5a, 0x4d, 0x63, 0x45, 0xe8, 0xf8, 0x50, 0x17, 0x2e, 0x26, 0xed, 0x27]);
+	assert_eq!(derive_rc4_key(12306624562963), [0x2d, 0xcb, 0xc1, 0x91, 0x56, 0x5d, 0x38, 0xc9, 0xa4, 0x11, 0xa6, 0xac, 0x78, 0x3f, 0x33, 0x77, 0x04, 0x7f, 0xe8, 0x1a]);
+}
+
+#[test]
+fn missing_key() {
+	assert!(matches!(decrypt(b"data", None), Err(Error::MissingDecryptionKey)));
+	assert!(matches!(decrypt(b"data", Some(0)), Err(Error::MissingDecryptionKey)));
+}
+
+#[test]
+fn decrypt_round_trip() {
+	let data = (0..=255u8).collect::<Vec<_>>();
+	let encrypted = decrypt(&data, Some(42)).map_err(|e| e.to_string()).unwrap();
+	assert_ne!(encrypted, data);
+	assert_eq!(decrypt(&encrypted, Some(42)).map_err(|e| e.to_string()).unwrap(), data);
+}
+
+#[test]
+fn decrypt_waveform() {
+	let data = std::fs::read("tests/res/3127451.abnk").unwrap();
+	let abnk = dut::pf::PackFileReader::<dut::formats::ABNK>::from_bytes(&data).map_err(|e| e.to_string()).unwrap()
+		.next().unwrap().map_err(|e| e.to_string()).unwrap();
+	let file = abnk.files.iter().find(|f| f.voice_id == 457667).unwrap();
+	let asnd = dut::pf::PackFileReader::<dut::formats::ASND>::from_bytes(file.audio_data).map_err(|e| e.to_string()).unwrap()
+		.next().unwrap().map_err(|e| e.to_string()).unwrap();
+
+	assert!(matches!(asnd.decrypt_audio(None), Err(Error::MissingDecryptionKey)));
+
+	// same output as the rust-crypto based implementation this replaced
+	let decrypted = asnd.decrypt_audio(Some(12306624562963)).map_err(|e| e.to_string()).unwrap();
+	assert_eq!(decrypted.len(), asnd.audio_data.len());
+	assert_eq!(&decrypted[..16], &[0xba, 0x8a, 0x19, 0xb0, 0x55, 0x5c, 0x48, 0xe3, 0x19, 0x21, 0xc9, 0x4a, 0x01, 0xbb, 0xe1, 0xaf]);
+	assert_eq!(&decrypted[decrypted.len() - 8..], &[0x8a, 0xd4, 0xb2, 0xca, 0xdd, 0xa8, 0x77, 0xa7]);
+}
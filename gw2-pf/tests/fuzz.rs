@@ -0,0 +1,166 @@
+//! Malformed input has to come back as a `parse::Error`, never as a panic or an allocation failure.
+//! Each format is fuzzed by mutating a small valid file and by feeding random chunk descriptors to every known chunk version.
+
+use std::fmt::Debug;
+use gw2_pf_rs as dut;
+use dut::{parse::{Input, ParseMagicVariant}, pf::{Magic, PackFileReader, RawPackFile}};
+use proptest::{prelude::*, sample::Index};
+
+#[derive(Debug, Clone)]
+enum Mutation {
+	Byte { index : Index, value : u8 },
+	/// Targets length and pointer fields, which are 4 byte aligned in the descriptors.
+	Word { index : Index, value : u32 },
+	Truncate { index : Index },
+}
+
+fn mutation() -> impl Strategy<Value = Mutation> {
+	let interesting_word = prop_oneof![
+		Just(0u32), Just(1), Just(3), Just(4), Just(8), Just(0x7fff_ffff), Just(0x8000_0000), Just(u32::MAX), any::<u32>(),
+	];
+	prop_oneof![
+		4 => (any::<Index>(), any::<u8>()).prop_map(|(index, value)| Mutation::Byte { index, value }),
+		4 => (any::<Index>(), interesting_word).prop_map(|(index, value)| Mutation::Word { index, value }),
+		1 => any::<Index>().prop_map(|index| Mutation::Truncate { index }),
+	]
+}
+
+/// Applies `mutations` to everything after the packfile header, so the file still gets recognized.
+fn mutate(seed : &[u8], mutations : &[Mutation]) -> Vec<u8> {
+	const HEADER_SIZE : usize = 12;
+	let mut data = seed.to_vec();
+	for mutation in mutations {
+		let body_len = data.len() - HEADER_SIZE;
+		if body_len < 4 { break }
+		match mutation {
+			Mutation::Byte { index, value } => data[HEADER_SIZE + index.index(body_len)] = *value,
+			Mutation::Word { index, value } => {
+				let position = HEADER_SIZE + index.index(body_len / 4) * 4;
+				data[position..][..4].copy_from_slice(&value.to_le_bytes());
+			},
+			Mutation::Truncate { index } => data.truncate(HEADER_SIZE + index.index(body_len)),
+		}
+	}
+	data
+}
+
+fn parse_all<'inp, F : Magic + ParseMagicVariant<'inp> + Debug + 'inp>(data : &'inp [u8]) -> Vec<F> {
+	let mut result = Vec::new();
+	let Ok(chunks) = PackFileReader::<F>::from_bytes(data).map_err(|e| e.to_string()) else { return result };
+	for chunk in chunks {
+		match chunk {
+			Ok(chunk) => { _ = format!("{chunk:?}"); result.push(chunk) },
+			Err(e) => _ = e.to_string(),
+		}
+	}
+	result
+}
+
+fn parse_abnk(data : &[u8]) {
+	for bank in parse_all::<dut::formats::ABNK>(data) {
+		for file in &bank.files {
+			parse_all::<dut::formats::ASND>(file.audio_data);
+			_ = file.audio_data.first();
+		}
+	}
+}
+
+/// Parses `body` as the descriptor of every chunk found in `seed`, for every version from 0 to the seeds version + 1.
+fn parse_chunk_bodies<'inp, F : ParseMagicVariant<'inp> + Debug>(seed : &[u8], body : &'inp [u8]) {
+	let file = RawPackFile::from_bytes(seed).unwrap();
+	let is_64_bit = file.header.is_64_bit();
+	for chunk in file.chunks {
+		let chunk = chunk.unwrap();
+		for version in 0..=chunk.version() + 1 {
+			let input = &mut Input::new(body, is_64_bit);
+			match F::parse(chunk.magic(), version, input) {
+				Ok(chunk) => _ = format!("{chunk:?}"),
+				Err(e) => _ = e.to_string(),
+			}
+		}
+	}
+}
+
+/// The real txtv sample is large, keep only a few mappings to get fast iterations.
+fn txtv_seed() -> &'static [u8] {
+	static SEED : std::sync::OnceLock<Vec<u8>> = std::sync::OnceLock::new();
+	SEED.get_or_init(build_txtv_seed)
+}
+
+fn build_txtv_seed() -> Vec<u8> {
+	let data = std::fs::read("tests/res/198300.txtv").unwrap();
+	let flags = u16::from_le_bytes([data[2], data[3]]);
+	let mut writer = dut::pf::PackFileWriter::<dut::formats::txtv>::new(flags);
+	for chunk in PackFileReader::<dut::formats::txtv>::from_bytes(&data).map_err(|e| e.to_string()).unwrap() {
+		let mut chunk = chunk.map_err(|e| e.to_string()).unwrap();
+		chunk.mappings.truncate(8);
+		writer.write_chunk(&chunk).map_err(|e| e.to_string()).unwrap();
+	}
+	writer.finish()
+}
+
+proptest! {
+	#![proptest_config(ProptestConfig::with_cases(512))]
+
+	#[test]
+	fn fuzz_abix(mutations in prop::collection::vec(mutation(), 1..8)) {
+		parse_all::<dut::formats::ABIX>(&mutate(&std::fs::read("tests/res/190445.abix").unwrap(), &mutations));
+	}
+
+	#[test]
+	fn fuzz_abnk(mutations in prop::collection::vec(mutation(), 1..8)) {
+		parse_abnk(&mutate(&std::fs::read("tests/res/3278880.abnk").unwrap(), &mutations));
+	}
+
+	#[test]
+	fn fuzz_asnd(mutations in prop::collection::vec(mutation(), 1..8)) {
+		parse_all::<dut::formats::ASND>(&mutate(&std::fs::read("tests/res/2788751.sound").unwrap(), &mutations));
+	}
+
+	#[test]
+	fn fuzz_txtv(mutations in prop::collection::vec(mutation(), 1..8)) {
+		parse_all::<dut::formats::txtv>(&mutate(txtv_seed(), &mutations));
+	}
+
+	#[test]
+	fn fuzz_chunk_bodies(body in prop::collection::vec(any::<u8>(), 0..256)) {
+		parse_chunk_bodies::<dut::formats::ABIX>(&std::fs::read("tests/res/190445.abix").unwrap(), &body);
+		parse_chunk_bodies::<dut::formats::ABNK>(&std::fs::read("tests/res/3278880.abnk").unwrap(), &body);
+		parse_chunk_bodies::<dut::formats::ASND>(&std::fs::read("tests/res/2788751.sound").unwrap(), &body);
+		parse_chunk_bodies::<dut::formats::txtv>(txtv_seed(), &body);
+	}
+
+	#[test]
+	fn fuzz_raw_packfile(data in prop::collection::vec(any::<u8>(), 0..128)) {
+		let mut data = data;
+		if data.len() >= 2 { data[..2].copy_from_slice(b"PF") }
+		if let Ok(file) = RawPackFile::from_bytes(&data) {
+			for chunk in file.chunks {
+				if let Err(e) = chunk { _ = e.to_string() }
+			}
+		}
+	}
+}
+
+#[test]
+fn reject_allocation_bomb() {
+	// a Vec<u32> length of u32::MAX with a valid pointer must not try to reserve 16 GiB
+	let mut body = Vec::new();
+	body.extend_from_slice(&u32::MAX.to_le_bytes());
+	body.extend_from_slice(&8u64.to_le_bytes());
+	body.extend_from_slice(&[0; 16]);
+	let result = <dut::formats::txtv as ParseMagicVariant>::parse(dut::fcc(b"txtv"), 0, &mut Input::new(&body, true));
+	assert!(matches!(result.as_ref().map_err(|e| e.innermost()), Err(dut::parse::Error::DataTooShort { .. })), "{result:?}");
+}
+
+#[test]
+fn pointer_to_directly_following_data() {
+	// a pointer value equal to the pointer size points right behind the pointer, only 0 is null
+	let data = [4, 0, 0, 0, 42, 0, 0, 0];
+	let value = <Option<u32> as dut::parse::Parse>::parse(&mut Input::new(&data, false)).map_err(|e| e.to_string()).unwrap();
+	assert_eq!(value, Some(42));
+
+	let data = [2, 0, 0, 0];
+	let value = <Option<u32> as dut::parse::Parse>::parse(&mut Input::new(&data, false));
+	assert!(matches!(value, Err(dut::parse::Error::InvalidPointer { value: 2 })));
+}
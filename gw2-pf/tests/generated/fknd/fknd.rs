@@ -0,0 +1,8 @@
+pub mod v0;
+
+#[derive(Debug, crate::Parse)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[chunk]
+pub enum FKND<'a> {
+	#[v(0)] V0(v0::FieldKinds<'a>),
+}
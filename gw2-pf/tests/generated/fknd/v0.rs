@@ -0,0 +1,53 @@
+use crate::{CString, FileName, Tagged};
+
+#[derive(Debug, crate::Parse)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FieldKinds<'a> {
+	pub inline      : [u16; 3],
+	pub fixed       : [Point; 2],
+	#[cfg_attr(feature = "serde", serde(serialize_with = "crate::serialize::serde_array"))]
+	pub long        : [u8; 40],
+	pub pointers    : Vec<Option<Named>>,
+	#[small]
+	pub small       : Vec<u32>,
+	#[small]
+	pub small_bytes : Vec<u8>,
+	pub data        : &'a [u8],
+	pub shape       : Tagged<Variant_5085761950254463568<'a>>,
+	pub origin      : Option<Point>,
+}
+
+#[derive(Debug, crate::Parse)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Point {
+	pub x : f32,
+	pub y : f32,
+}
+
+#[derive(Debug, crate::Parse)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Named {
+	pub name : Option<FileName>,
+}
+
+#[derive(Debug, crate::Parse)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[variant]
+#[allow(non_camel_case_types)]
+pub enum Variant_5085761950254463568<'a> {
+	Var0(Circle),
+	Var1(Label<'a>),
+}
+
+#[derive(Debug, crate::Parse)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Circle {
+	pub center : Point,
+	pub radius : f32,
+}
+
+#[derive(Debug, crate::Parse)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Label<'a> {
+	pub text : CString<'a>,
+}
@@ -0,0 +1,91 @@
+use gw2_pf_rs as dut;
+
+mod common;
+
+/// Overwrites `bits` bits at bit position `position` of the stream, in the order the decompressor reads them.
+fn write_bits(stream : &mut [u8], position : usize, value : u32, bits : usize) {
+	for i in 0..bits {
+		let bit = position + i;
+		let byte = bit / 32 * 4 + (31 - bit % 32) / 8;
+		let mask = 1 << ((31 - bit % 32) % 8);
+		if value >> (bits - 1 - i) & 1 != 0 { stream[byte] |= mask } else { stream[byte] &= !mask }
+	}
+}
+
+#[test]
+fn inflate_empty() {
+	let compressed = common::compress(&[]);
+	assert_eq!(dut::compression::inflated_size(&compressed).map_err(|e| e.to_string()).unwrap(), 0);
+	assert_eq!(dut::compression::inflate(&compressed).map_err(|e| e.to_string()).unwrap(), Vec::<u8>::new());
+}
+
+#[test]
+fn inflate_text() {
+	let data = b"abcabcabcabcabcabc hello hello hello world, hello world!".repeat(20);
+	let compressed = common::compress(&data);
+	assert!(compressed.len() < data.len());
+	assert_eq!(dut::compression::inflated_size(&compressed).map_err(|e| e.to_string()).unwrap(), data.len());
+	assert_eq!(dut::compression::inflate(&compressed).map_err(|e| e.to_string()).unwrap(), data);
+}
+
+#[test]
+fn inflate_multiple_blocks() {
+	// more than 0x10000 codes and 0x4000 dwords, so trees are read again and crc dwords are skipped
+	let mut data = Vec::new();
+	for path in ["tests/res/179764.abnk", "tests/res/2788751.sound", "tests/res/198300.txtv", "tests/res/184691"] {
+		data.extend(std::fs::read(path).unwrap());
+	}
+	let mut state = 0x1234_5678u32;
+	data.extend((0..0x20000).map(|_| { state = state.wrapping_mul(1664525).wrapping_add(1013904223); (state >> 24) as u8 }));
+
+	let compressed = common::compress(&data);
+	assert!(compressed.len() > 0x4000 * 4);
+	assert_eq!(dut::compression::inflate(&compressed).map_err(|e| e.to_string()).unwrap(), data);
+}
+
+#[test]
+fn inflate_into_slice() {
+	let data = b"0123456789".repeat(100);
+	let compressed = common::compress(&data);
+
+	let output = &mut [0; 2000];
+	let written = dut::compression::inflate_into(&compressed, output).map_err(|e| e.to_string()).unwrap();
+	assert_eq!(&output[..written], data);
+
+	let output = &mut [0; 15];
+	let written = dut::compression::inflate_into(&compressed, output).map_err(|e| e.to_string()).unwrap();
+	assert_eq!(written, 15);
+	assert_eq!(output, &data[..15]);
+}
+
+#[test]
+fn reject_corrupt_data() {
+	let data = b"some data, some more data, and even more data".repeat(50);
+	let compressed = common::compress(&data);
+
+	let truncated = &compressed[..compressed.len() / 2];
+	assert!(matches!(dut::compression::inflate(truncated), Err(dut::parse::Error::DataTooShort { .. })));
+	assert!(matches!(dut::compression::inflate(&compressed[..6]), Err(dut::parse::Error::DataTooShort { .. })));
+
+	// header, copy size nibble, both trees and the max code count, see `common::compress`
+	let first_code_bit = 64 + 8 + (16 + 35 * 10 + 9) + (16 + 4 * 13 + 7) + 4;
+
+	let mut bad_code = compressed.clone();
+	write_bits(&mut bad_code, first_code_bit, 0, 1);
+	assert!(matches!(dut::compression::inflate(&bad_code), Err(dut::parse::Error::InvalidHuffmanCode)));
+
+	// a copy right at the start has nothing to copy from
+	let mut bad_copy = compressed.clone();
+	write_bits(&mut bad_copy, first_code_bit, 511 - 0x100, 9);
+	write_bits(&mut bad_copy, first_code_bit + 9, 63, 6);
+	assert!(matches!(dut::compression::inflate(&bad_copy), Err(dut::parse::Error::CopyOutOfBounds { offset: 1, position: 0 })));
+
+	// flipping bits anywhere must never panic
+	for i in 8..compressed.len() {
+		for bit in 0..8 {
+			let mut corrupt = compressed.clone();
+			corrupt[i] ^= 1 << bit;
+			let _ = dut::compression::inflate(&corrupt);
+		}
+	}
+}
@@ -0,0 +1,49 @@
+use gw2_pf_rs as dut;
+use dut::{parse::{Error, Input, Parse, PathSegment}, LazyArray, WString};
+
+#[test]
+fn elements_are_parsed_on_access() {
+	// three Option<u32>, the last one with a pointer into itself
+	let mut data = Vec::new();
+	data.extend_from_slice(&3u32.to_le_bytes());
+	data.extend_from_slice(&4u32.to_le_bytes());
+	data.extend_from_slice(&12u32.to_le_bytes()); // -> 20
+	data.extend_from_slice(&0u32.to_le_bytes());
+	data.extend_from_slice(&2u32.to_le_bytes());
+	data.extend_from_slice(&42u32.to_le_bytes());
+
+	let array = LazyArray::<Option<u32>>::parse(&mut Input::new(&data, false)).map_err(|e| e.to_string()).unwrap();
+	assert_eq!(array.len(), 3);
+	assert_eq!(array.get(0).unwrap().map_err(|e| e.to_string()).unwrap(), Some(42));
+	assert_eq!(array.get(1).unwrap().map_err(|e| e.to_string()).unwrap(), None);
+	let Some(Err(Error::Located { path, .. })) = array.get(2) else { panic!() };
+	assert_eq!(path, [PathSegment::Index(2)]);
+	assert!(array.get(3).is_none());
+
+	// iteration stops after the first error
+	assert_eq!(array.iter().count(), 3);
+	assert!(array.to_vec().is_err());
+}
+
+#[test]
+fn dynamically_sized_elements() {
+	let mut data = Vec::new();
+	data.extend_from_slice(&2u32.to_le_bytes());
+	data.extend_from_slice(&4u32.to_le_bytes());
+	for c in "ab\0c\0".encode_utf16() { data.extend_from_slice(&c.to_le_bytes()) }
+
+	let array = LazyArray::<WString>::parse(&mut Input::new(&data, false)).map_err(|e| e.to_string()).unwrap();
+	assert_eq!(String::from_utf16_lossy(&array.get(1).unwrap().map_err(|e| e.to_string()).unwrap()), "c");
+	let all = array.to_vec().map_err(|e| e.to_string()).unwrap();
+	assert_eq!(all.iter().map(|s| String::from_utf16_lossy(s)).collect::<Vec<_>>(), ["ab", "c"]);
+}
+
+#[test]
+fn reject_length_beyond_data() {
+	let mut data = Vec::new();
+	data.extend_from_slice(&u32::MAX.to_le_bytes());
+	data.extend_from_slice(&4u32.to_le_bytes());
+	data.extend_from_slice(&[0; 16]);
+	let result = LazyArray::<u32>::parse(&mut Input::new(&data, false));
+	assert!(matches!(result, Err(Error::DataTooShort { .. })));
+}
@@ -0,0 +1,54 @@
+use gw2_pf_rs as dut;
+use dut::parse::{Error, Input, Limit, Limits, Parse};
+
+fn parse_abnk(data : &[u8], limits : Limits) -> Result<Vec<dut::formats::ABNK<'_>>, Error> {
+	dut::pf::PackFileReader::<dut::formats::ABNK>::from_bytes_with_limits(data, limits)?.collect()
+}
+
+#[test]
+fn unlimited_by_default() {
+	assert_eq!(Limits::default(), Limits::UNLIMITED);
+
+	let data = std::fs::read("tests/res/179764.abnk").unwrap();
+	let expected = dut::pf::PackFileReader::<dut::formats::ABNK>::from_bytes(&data).map_err(|e| e.to_string()).unwrap()
+		.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string()).unwrap();
+	let limited = parse_abnk(&data, Limits { max_total_allocation: 1 << 20, max_array_length: 1 << 16, max_depth: 8 }).map_err(|e| e.to_string()).unwrap();
+	assert_eq!(format!("{expected:?}"), format!("{limited:?}"));
+}
+
+#[test]
+fn reject_array_length() {
+	let data = std::fs::read("tests/res/179764.abnk").unwrap();
+	let files = parse_abnk(&data, Limits::UNLIMITED).map_err(|e| e.to_string()).unwrap()[0].files.len();
+
+	let result = parse_abnk(&data, Limits { max_array_length: files - 1, ..Limits::UNLIMITED });
+	assert!(matches!(result.as_ref().map_err(|e| e.innermost()), Err(Error::LimitExceeded { limit: Limit::ArrayLength, actual, max }) if *actual == files && *max == files - 1), "{result:?}");
+	assert!(parse_abnk(&data, Limits { max_array_length: files, ..Limits::UNLIMITED }).is_ok());
+}
+
+#[test]
+fn reject_total_allocation() {
+	// the limit is shared by all arrays of the file, a single array of 4 u32 fits but two don't
+	let mut data = Vec::new();
+	for offset in [12u32, 4] {
+		data.extend_from_slice(&4u32.to_le_bytes());
+		data.extend_from_slice(&offset.to_le_bytes());
+	}
+	data.extend_from_slice(&[1; 16]);
+
+	let limits = Limits { max_total_allocation: 16, ..Limits::UNLIMITED };
+	let input = &mut Input::with_limits(&data, false, limits);
+	assert_eq!(Vec::<u32>::parse(input).map_err(|e| e.to_string()).unwrap(), [0x01010101; 4]);
+	let result = Vec::<u32>::parse(input);
+	assert!(matches!(result, Err(Error::LimitExceeded { limit: Limit::TotalAllocation, actual: 32, max: 16 })), "{result:?}");
+}
+
+#[test]
+fn reject_depth() {
+	// Option<Option<Option<u8>>>, every pointer points directly behind itself
+	let data = [4, 0, 0, 0, 4, 0, 0, 0, 4, 0, 0, 0, 42];
+	let parse = |max_depth| Option::<Option<Option<u8>>>::parse(&mut Input::with_limits(&data, false, Limits { max_depth, ..Limits::UNLIMITED }));
+
+	assert!(matches!(parse(3), Ok(Some(Some(Some(42))))));
+	assert!(matches!(parse(2), Err(Error::LimitExceeded { limit: Limit::Depth, actual: 3, max: 2 })));
+}
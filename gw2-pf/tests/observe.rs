@@ -0,0 +1,101 @@
+use std::sync::{Arc, Mutex};
+use gw2_pf_rs as dut;
+use dut::{observe::{Coverage, Node, ParseObserver}, parse::{Error, Input, Parse}};
+
+#[derive(Debug, PartialEq)]
+enum Event {
+	Enter(Node, usize),
+	Exit(Node, usize, bool),
+	Pointer(usize, Option<usize>),
+	Array(usize, Option<usize>),
+}
+
+#[derive(Default)]
+struct Recorder(Mutex<Vec<Event>>);
+
+impl ParseObserver for Recorder {
+	fn enter(&self, node : Node, offset : usize) { self.0.lock().unwrap().push(Event::Enter(node, offset)) }
+	fn exit(&self, node : Node, offset : usize, error : Option<&Error>) { self.0.lock().unwrap().push(Event::Exit(node, offset, error.is_some())) }
+	fn pointer(&self, at : usize, target : Option<usize>) { self.0.lock().unwrap().push(Event::Pointer(at, target)) }
+	fn array(&self, _element_type : &'static str, length : usize, target : Option<usize>) { self.0.lock().unwrap().push(Event::Array(length, target)) }
+}
+
+fn parse_observed(data : &[u8]) -> (Vec<Event>, dut::parse::Result<Vec<dut::formats::ABNK<'_>>>) {
+	let recorder = Arc::new(Recorder::default());
+	let result = dut::pf::PackFileReader::<dut::formats::ABNK>::from_bytes(data).map_err(|e| e.to_string()).unwrap()
+		.with_observer(recorder.clone())
+		.collect::<Result<Vec<_>, _>>();
+	let events = std::mem::take(&mut *recorder.0.lock().unwrap());
+	(events, result)
+}
+
+#[test]
+fn nodes_are_nested() {
+	let data = std::fs::read("tests/res/179764.abnk").unwrap();
+	let (events, result) = parse_observed(&data);
+	result.map_err(|e| e.to_string()).unwrap();
+
+	let mut stack = Vec::new();
+	for event in &events {
+		match *event {
+			Event::Enter(node, offset) => stack.push((node, offset)),
+			Event::Exit(node, end, failed) => {
+				let (entered, start) = stack.pop().unwrap();
+				assert_eq!(entered, node);
+				assert!(start <= end && end <= data.len());
+				assert!(!failed);
+			},
+			_ => {},
+		}
+	}
+	assert!(stack.is_empty());
+	assert!(events.contains(&Event::Enter(Node::Field("BKCK"), 0x1c)), "{events:?}");
+}
+
+#[test]
+fn pointers_resolve_to_parsed_data() {
+	let data = std::fs::read("tests/res/179764.abnk").unwrap();
+	let (events, result) = parse_observed(&data);
+	let banks = result.map_err(|e| e.to_string()).unwrap();
+
+	// every audio slice is announced as an array at the position it was taken from
+	for file in &banks[0].files {
+		let position = file.audio_data.as_ptr() as usize - data.as_ptr() as usize;
+		assert!(events.contains(&Event::Array(file.audio_data.len(), Some(position))), "{position:#x}");
+	}
+	for event in &events {
+		if let Event::Pointer(at, Some(target)) = *event { assert!(at < target && target <= data.len()) }
+	}
+}
+
+#[test]
+fn failures_are_reported() {
+	// length of the audio data of the first file
+	let mut data = std::fs::read("tests/res/179764.abnk").unwrap();
+	data[0x60..0x64].copy_from_slice(&u32::MAX.to_le_bytes());
+	let (events, result) = parse_observed(&data);
+	assert!(result.is_err());
+	assert!(events.iter().any(|e| matches!(e, Event::Exit(Node::Field("audio_data"), _, true))), "{events:?}");
+	assert!(matches!(events.last(), Some(Event::Exit(Node::Field("ABNK"), _, true))), "{events:?}");
+}
+
+#[test]
+fn coverage_follows_pointers() {
+	// Option<u32> pointing over 4 bytes of padding
+	let data = [8, 0, 0, 0, 0xff, 0xff, 0xff, 0xff, 42, 0, 0, 0, 0xff];
+	let coverage = Arc::new(Coverage::new());
+	let value = Option::<u32>::parse(&mut Input::new(&data, false).with_coverage(coverage.clone())).map_err(|e| e.to_string()).unwrap();
+	assert_eq!(value, Some(42));
+	assert_eq!(coverage.read_ranges(), [0..4, 8..12]);
+	assert_eq!(coverage.unread_ranges(0..data.len()), [4..8, 12..13]);
+}
+
+#[test]
+fn unread_chunk_ranges() {
+	let data = std::fs::read("tests/res/2788751.sound").unwrap();
+	let chunk = dut::pf::RawPackFile::from_bytes(&data).map_err(|e| e.to_string()).unwrap().chunks.next().unwrap().map_err(|e| e.to_string()).unwrap();
+	let (_, unread) = chunk.parse_with_coverage::<dut::formats::ASND>().map_err(|e| e.to_string()).unwrap();
+	assert_eq!(chunk.data_range(), 0x1c..0xe94);
+	// padding between the header fields and the audio data
+	assert_eq!(unread, vec![std::ops::Range { start: 0x68, end: 0x6c }]);
+}
@@ -0,0 +1,113 @@
+use gw2_pf_rs as dut;
+use dut::serialize::SerializeVersioned;
+
+#[test]
+fn walk_raw_chunks() {
+	for path in std::fs::read_dir("tests/res").unwrap() {
+		let path = path.unwrap();
+		if path.file_type().unwrap().is_dir() { continue }
+
+		let data = std::fs::read(path.path().to_str().unwrap()).unwrap();
+		let Ok(file) = dut::pf::RawPackFile::from_bytes(&data) else { continue };
+		assert_eq!(file.header.header_size, 12);
+
+		let is_64_bit = file.header.is_64_bit();
+		let chunks = file.chunks.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string()).unwrap();
+		assert!(!chunks.is_empty(), "{:?}", path.file_name());
+		for chunk in chunks {
+			assert_eq!(chunk.header.chunk_header_size, 16);
+			assert_eq!(chunk.data.len(), chunk.header.descriptor_offset as usize);
+			assert_eq!(chunk.is_64_bit, is_64_bit);
+		}
+	}
+}
+
+#[test]
+fn raw_chunks_match_typed() {
+	let data = std::fs::read("tests/res/179764.abnk").unwrap();
+	let file = dut::pf::RawPackFile::from_bytes(&data).map_err(|e| e.to_string()).unwrap();
+	assert_eq!(file.header.file_type, dut::fcc(b"ABNK"));
+	assert!(!file.header.is_64_bit());
+
+	let chunks = file.chunks.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string()).unwrap();
+	assert_eq!(chunks.len(), 1);
+	assert_eq!(chunks[0].magic(), dut::fcc(b"BKCK"));
+
+	let typed = chunks[0].parse::<dut::formats::ABNK>().map_err(|e| e.to_string()).unwrap();
+	let dut::formats::ABNK::BKCK(bkck) = &typed;
+	assert_eq!(bkck.version(), chunks[0].version());
+	let expected = dut::pf::PackFileReader::<dut::formats::ABNK>::from_bytes(&data).map_err(|e| e.to_string()).unwrap().next().unwrap().map_err(|e| e.to_string()).unwrap();
+	assert_eq!(format!("{typed:?}"), format!("{expected:?}"));
+}
+
+#[test]
+fn reject_non_packfile() {
+	assert!(matches!(dut::pf::RawPackFile::from_bytes(b"XX\x01\0"), Err(dut::parse::Error::DataTooShort { .. })));
+	assert!(matches!(dut::pf::RawPackFile::from_bytes(b"XF\x01\0\0\0\x0c\0ABNK"), Err(dut::parse::Error::InvalidFileType { .. })));
+}
+
+#[test]
+fn reject_out_of_bounds_headers() {
+	use dut::parse::Error;
+
+	let data = std::fs::read("tests/res/179764.abnk").unwrap();
+	let patched = |offset : usize, value : &[u8]| {
+		let mut data = data.clone();
+		data[offset..][..value.len()].copy_from_slice(value);
+		data
+	};
+	let first_chunk_error = |data : &[u8]| {
+		let mut chunks = dut::pf::RawPackFile::from_bytes(data).map_err(|e| e.to_string()).unwrap().chunks;
+		let error = chunks.next().unwrap().err();
+		assert!(chunks.next().is_none());
+		error
+	};
+
+	let header_size = patched(6, &u16::MAX.to_le_bytes());
+	assert!(matches!(dut::pf::RawPackFile::from_bytes(&header_size[..1000]), Err(Error::HeaderFieldOutOfBounds { field: "header_size", .. })));
+	let header_size = patched(6, &4u16.to_le_bytes());
+	assert!(matches!(dut::pf::RawPackFile::from_bytes(&header_size), Err(Error::HeaderFieldOutOfBounds { field: "header_size", .. })));
+
+	// chunk header starts at 12: magic, next_chunk_offset, version, chunk_header_size, descriptor_offset
+	let next_chunk_offset = patched(12 + 4, &u32::MAX.to_le_bytes());
+	assert!(matches!(first_chunk_error(&next_chunk_offset), Some(Error::HeaderFieldOutOfBounds { field: "next_chunk_offset", .. })));
+	let chunk_header_size = patched(12 + 10, &u16::MAX.to_le_bytes());
+	assert!(matches!(first_chunk_error(&chunk_header_size[..1000]), Some(Error::HeaderFieldOutOfBounds { field: "chunk_header_size", .. })));
+	let descriptor_offset = patched(12 + 12, &u32::MAX.to_le_bytes());
+	assert!(matches!(first_chunk_error(&descriptor_offset), Some(Error::HeaderFieldOutOfBounds { field: "descriptor_offset", .. })));
+
+	// a tail that is too short for a chunk header ends the iteration
+	assert!(dut::pf::RawPackFile::from_bytes(&data[..27]).map_err(|e| e.to_string()).unwrap().chunks.next().is_none());
+	let mut padded = data.clone();
+	padded.extend_from_slice(&[0; 15]);
+	let chunks = dut::pf::RawPackFile::from_bytes(&padded).map_err(|e| e.to_string()).unwrap().chunks;
+	assert_eq!(chunks.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string()).unwrap().len(), 1);
+
+	let typed = dut::pf::PackFileReader::<dut::formats::ABNK>::from_bytes(&descriptor_offset).map_err(|e| e.to_string()).unwrap().next().unwrap();
+	assert!(matches!(typed, Err(Error::HeaderFieldOutOfBounds { .. })));
+}
+
+#[test]
+fn error_location() {
+	use dut::parse::{Error, PathSegment};
+
+	let data = std::fs::read("tests/res/179764.abnk").unwrap();
+	let bank = dut::pf::PackFileReader::<dut::formats::ABNK>::from_bytes(&data).map_err(|e| e.to_string()).unwrap()
+		.next().unwrap().map_err(|e| e.to_string()).unwrap();
+	let audio_data = bank.files[1].audio_data;
+	let audio_offset = audio_data.as_ptr() as usize - data.as_ptr() as usize;
+
+	// find the length and relative pointer of the slice to break it, the file uses 32 bit pointers
+	let read_u32 = |p : usize| u32::from_le_bytes(data[p..][..4].try_into().unwrap()) as usize;
+	let field_offset = (0..audio_offset - 8).find(|&p| read_u32(p) == audio_data.len() && read_u32(p + 4) == audio_offset - (p + 4)).unwrap();
+	let mut corrupted = data.clone();
+	corrupted[field_offset..][..4].copy_from_slice(&u32::MAX.to_le_bytes());
+
+	let error = dut::pf::PackFileReader::<dut::formats::ABNK>::from_bytes(&corrupted).map_err(|e| e.to_string()).unwrap()
+		.next().unwrap().unwrap_err();
+	let Error::Located { path, offset, .. } = &error else { panic!("{error:?}") };
+	assert_eq!(*offset, field_offset);
+	assert_eq!(path[4..], [PathSegment::Index(1), PathSegment::Field("audio_data".into())]);
+	assert!(matches!(error.innermost(), Error::DataTooShort { .. }));
+	assert!(error.to_string().starts_with(&format!("ABNK.BKCK.V2.files[1].audio_data @ {field_offset:#x}: ")), "{error}");
+}
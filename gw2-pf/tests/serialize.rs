@@ -0,0 +1,66 @@
+use gw2_pf_rs as dut;
+
+/// parse -> write -> parse, then check that both parses match and that writing again is stable
+macro_rules! round_trip {
+	($format:ty, $path:literal) => {{
+		let data = std::fs::read($path).unwrap();
+		let flags = u16::from_le_bytes([data[2], data[3]]);
+
+		let original = dut::pf::PackFileReader::<$format>::from_bytes(&data).map_err(|e| e.to_string()).unwrap()
+			.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string()).unwrap();
+
+		let mut writer = dut::pf::PackFileWriter::<$format>::new(flags);
+		for chunk in &original { writer.write_chunk(chunk).map_err(|e| e.to_string()).unwrap(); }
+		let written = writer.finish();
+
+		let reparsed = dut::pf::PackFileReader::<$format>::from_bytes(&written).map_err(|e| e.to_string()).unwrap()
+			.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string()).unwrap();
+
+		assert_eq!(format!("{original:?}"), format!("{reparsed:?}"));
+
+		let mut writer = dut::pf::PackFileWriter::<$format>::new(flags);
+		for chunk in &reparsed { writer.write_chunk(chunk).map_err(|e| e.to_string()).unwrap(); }
+		assert_eq!(written, writer.finish());
+	}};
+}
+
+#[test]
+fn round_trip_abix() {
+	round_trip!(dut::formats::ABIX, "tests/res/184691");
+}
+
+#[test]
+fn round_trip_abnk() {
+	round_trip!(dut::formats::ABNK, "tests/res/179764.abnk");
+}
+
+#[test]
+fn round_trip_asnd_64_bit() {
+	round_trip!(dut::formats::ASND, "tests/res/2788751.sound");
+}
+
+#[test]
+fn round_trip_txtv_64_bit() {
+	round_trip!(dut::formats::txtv, "tests/res/198300.txtv");
+}
+
+#[test]
+fn round_trip_nested_asnd() {
+	let data = std::fs::read("tests/res/179764.abnk").unwrap();
+	let mut file = dut::pf::PackFileReader::<dut::formats::ABNK>::from_bytes(&data).map_err(|e| e.to_string()).unwrap();
+	let bank = file.next().unwrap().map_err(|e| e.to_string()).unwrap();
+
+	for asnd_file in bank.files.iter().filter(|f| !f.audio_data.is_empty()) {
+		let flags = u16::from_le_bytes([asnd_file.audio_data[2], asnd_file.audio_data[3]]);
+		let original = dut::pf::PackFileReader::<dut::formats::ASND>::from_bytes(asnd_file.audio_data).map_err(|e| e.to_string()).unwrap()
+			.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string()).unwrap();
+
+		let mut writer = dut::pf::PackFileWriter::<dut::formats::ASND>::new(flags);
+		for chunk in &original { writer.write_chunk(chunk).map_err(|e| e.to_string()).unwrap(); }
+		let written = writer.finish();
+
+		let reparsed = dut::pf::PackFileReader::<dut::formats::ASND>::from_bytes(&written).map_err(|e| e.to_string()).unwrap()
+			.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string()).unwrap();
+		assert_eq!(format!("{original:?}"), format!("{reparsed:?}"));
+	}
+}
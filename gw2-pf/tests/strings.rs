@@ -0,0 +1,95 @@
+use std::ffi::OsString;
+use gw2_pf_rs as dut;
+use dut::{parse::{Input, Parse}, FileName, FileNameKind, FileNameRef, WStr, WString};
+
+fn utf16(text : &str) -> Vec<u8> {
+	text.encode_utf16().chain([0]).flat_map(u16::to_le_bytes).collect()
+}
+
+#[test]
+fn borrowed_string_points_into_input() {
+	// starts at an odd address to make sure nothing relies on alignment
+	let mut data = vec![0xff];
+	data.extend(utf16("Grüße"));
+	data.extend(utf16("x"));
+
+	let input = &mut Input::new(&data[1..], false);
+	let string = WStr::parse(input).map_err(|e| e.to_string()).unwrap();
+	assert_eq!(string.as_bytes().as_ptr(), data[1..].as_ptr());
+	assert_eq!(string.len(), 5);
+	assert_eq!(string.get(2), Some('ü' as u16));
+	assert_eq!(string.get(5), None);
+	assert_eq!(string.get(usize::MAX / 2), None);
+	assert_eq!(string.get(usize::MAX), None);
+	assert_eq!(string.to_string(), "Grüße");
+	assert_eq!(String::from(string), "Grüße");
+	assert_eq!(OsString::from(string), OsString::from("Grüße"));
+	assert_eq!(*WString::from(string), "Grüße".encode_utf16().collect::<Vec<_>>());
+	assert_eq!(WStr::parse(input).map_err(|e| e.to_string()).unwrap().to_string(), "x");
+	assert!(input.remaining.is_empty());
+
+	assert!(WStr::parse(&mut Input::new(&[b'a', 0, b'b'], false)).is_err());
+}
+
+#[test]
+fn borrowed_file_name_matches_owned() {
+	let data = [0x01, 0x01, 0x02, 0x01, 0, 0];
+	let owned = FileName::parse(&mut Input::new(&data, false)).map_err(|e| e.to_string()).unwrap();
+	let borrowed = FileNameRef::parse(&mut Input::new(&data, false)).map_err(|e| e.to_string()).unwrap();
+	assert_eq!(borrowed.to_id(), owned.to_id());
+	assert_eq!(FileName::from(borrowed).to_id(), owned.to_id());
+
+	let empty = FileNameRef::parse(&mut Input::new(&[0, 0], false)).map_err(|e| e.to_string()).unwrap();
+	assert_eq!(empty.to_id(), 0);
+}
+
+#[test]
+fn file_name_ids() {
+	for id in [0, 1, 0xff00, 0xff01, 157442, 404553, FileName::MAX_ID - 1, FileName::MAX_ID] {
+		let name = FileName::from_id(id).unwrap();
+		assert_eq!(name.to_id(), id);
+		let expected = if id == 0 { FileNameKind::NoFile } else { FileNameKind::Id(id) };
+		assert_eq!(name.kind(), expected);
+	}
+	assert!(FileName::from_id(FileName::MAX_ID + 1).is_none());
+
+	// the packed encoding as found in the files
+	let packed = FileName::parse(&mut Input::new(&[0x02, 0x01, 0x03, 0x01, 0, 0], false)).map_err(|e| e.to_string()).unwrap();
+	assert_eq!(packed, FileName::from_id(195843).unwrap());
+	assert_eq!(packed.to_string(), "195843");
+
+	let text = FileName::parse(&mut Input::new(&utf16("a.dat"), false)).map_err(|e| e.to_string()).unwrap();
+	assert_eq!(text.kind(), FileNameKind::Other);
+	assert_eq!(text.to_id(), 0);
+	assert_eq!(text.to_string(), "a.dat");
+
+	let mut names = [text, FileName::from_id(0xff01).unwrap(), FileName::from_id(2).unwrap(), FileName::from_id(0).unwrap()];
+	names.sort();
+	assert_eq!(names.iter().map(|n| n.kind()).collect::<Vec<_>>(), [FileNameKind::NoFile, FileNameKind::Id(2), FileNameKind::Id(0xff01), FileNameKind::Other]);
+}
+
+#[test]
+fn borrowed_file_name_kinds() {
+	let data = std::fs::read("tests/res/184691").unwrap();
+	let chunk = dut::pf::PackFileReader::<dut::formats::ABIX>::from_bytes(&data).map_err(|e| e.to_string()).unwrap().next().unwrap().map_err(|e| e.to_string()).unwrap();
+	let names = chunk.bank_language.iter()
+		.flat_map(|l| l.bank_file_name.iter().map(|f| f.map_err(|e| e.to_string()).unwrap().file_name))
+		.flatten().collect::<Vec<_>>();
+	assert!(names.iter().all(|n| matches!(n.kind(), FileNameKind::NoFile | FileNameKind::Id(_))));
+	for name in names {
+		let owned = FileName::from(name);
+		assert_eq!(owned.kind(), name.kind());
+		assert_eq!(owned.to_string(), name.to_string());
+		assert_eq!(FileName::from_id(name.to_id()).unwrap(), owned);
+	}
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn file_name_serde() {
+	let names = [FileName::from_id(0).unwrap(), FileName::from_id(157442).unwrap(), FileName::from(WString::from("a.dat".encode_utf16().collect::<Vec<_>>()))];
+	let json = serde_json::to_string(&names).unwrap();
+	assert_eq!(json, r#"[0,157442,"a.dat"]"#);
+	assert_eq!(serde_json::from_str::<Vec<FileName>>(&json).unwrap(), names);
+	assert!(serde_json::from_str::<FileName>(&u64::from(u32::MAX).to_string()).is_err());
+}
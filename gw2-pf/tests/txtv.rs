@@ -23,7 +23,7 @@ fn extract_all() {
 }
 
 fn extract(data : &[u8], filename : &str) {
-	let file = &mut dut::pf::PackFileReader::<dut::formats::txtv>::from_bytes(&data).map_err(|e| e.to_string()).unwrap();
+	let file = &mut dut::pf::PackFileReader::<dut::formats::txtv>::from_bytes(data).map_err(|e| e.to_string()).unwrap();
 
 	let destination = &mut File::options().create(true).truncate(true).write(true).open(format!("tests/out/{filename}.csv")).unwrap();
 
@@ -0,0 +1,62 @@
+use gw2_pf_rs as dut;
+use dut::{parse::{Input, Parse}, serialize::{Output, Serialize}, CString, FileNameKind, FileRef, Token, WideCString, UUID};
+
+/// parse -> write, the written data has to match the original
+fn round_trip<'inp, T : Parse<'inp> + Serialize>(data : &'inp [u8]) -> T {
+	let value = T::parse(&mut Input::new(data, false)).map_err(|e| e.to_string()).unwrap();
+	let mut output = Output::new(false);
+	value.serialize(&mut output).map_err(|e| e.to_string()).unwrap();
+	assert_eq!(output.finish().map_err(|e| e.to_string()).unwrap(), data);
+	value
+}
+
+#[test]
+fn file_ref() {
+	let file_ref = round_trip::<FileRef>(&[0x02, 0x01, 0x03, 0x01, 0, 0]);
+	assert_eq!(file_ref.kind(), FileNameKind::Id(195843));
+	assert_eq!(file_ref, FileRef::from_id(195843).unwrap());
+	assert_eq!(file_ref.to_file_name(), dut::FileName::from_id(195843).unwrap());
+	assert_eq!(file_ref.to_string(), "195843");
+	assert_eq!(format!("{file_ref:?}"), "FileRef(195843)");
+
+	assert_eq!(round_trip::<FileRef>(&[0; 6]).kind(), FileNameKind::NoFile);
+	assert!(FileRef::parse(&mut Input::new(&[0; 5], false)).is_err());
+}
+
+#[test]
+fn token() {
+	let token = round_trip::<Token>(&0x0123_4567_89ab_cdefu64.to_le_bytes());
+	assert_eq!(token.value(), 0x0123_4567_89ab_cdef);
+	assert_eq!(token.to_string(), "0x0123456789abcdef");
+}
+
+#[test]
+fn uuid() {
+	let bytes = [0x33, 0x22, 0x11, 0x00, 0x55, 0x44, 0x77, 0x66, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff];
+	let uuid = round_trip::<UUID>(&bytes);
+	assert_eq!(uuid.to_string(), "00112233-4455-6677-8899-aabbccddeeff");
+	assert!(!uuid.is_nil());
+	assert!(UUID::parse(&mut Input::new(&bytes[..15], false)).is_err());
+}
+
+#[test]
+fn c_strings() {
+	let mut data = 4u32.to_le_bytes().to_vec();
+	data.extend_from_slice(b"abc\0");
+	let string = round_trip::<CString>(&data);
+	assert_eq!(string.to_str(), Ok("abc"));
+	assert_eq!(string.to_string(), "abc");
+
+	let mut data = 4u32.to_le_bytes().to_vec();
+	data.extend("äb\0".encode_utf16().flat_map(u16::to_le_bytes));
+	let string = round_trip::<WideCString>(&data);
+	assert_eq!(string.to_string_lossy(), "äb");
+	assert_eq!(string.to_string(), "äb");
+
+	let null = round_trip::<CString>(&[0; 4]);
+	assert!(null.is_null());
+	assert_eq!(null.as_bytes(), b"");
+	assert!(round_trip::<WideCString>(&[0; 4]).is_null());
+
+	assert!(CString::parse(&mut Input::new(&[4, 0, 0, 0, b'a'], false)).is_err());
+}